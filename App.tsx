@@ -7,6 +7,7 @@ import { Sidebar } from "./components/Sidebar";
 import { PlayerControls } from "./components/PlayerControls";
 import { useVibeStore } from "./store/vibeStore";
 import { exportController } from "./export/ExportController";
+import { projectController } from "./project/ProjectController";
 import { isTauri, normalizeFilePath, tauriConvertFileSrc, tauriDialogs } from "./platform/tauriEnv";
 import { version } from "./package.json";
 
@@ -95,7 +96,10 @@ const App: React.FC = () => {
 
     if (type === "image") {
       const imagePathRaw = await dialogs.open({
-        filters: [{ name: "Image", extensions: ["png", "jpg", "jpeg", "webp"] }],
+        filters: [
+          { name: "Image", extensions: ["png", "jpg", "jpeg", "webp", "gif", "apng"] },
+          { name: "Video", extensions: ["mp4", "mov", "m4v", "webm"] },
+        ],
         multiple: false,
       });
       if (!imagePathRaw || Array.isArray(imagePathRaw)) return;
@@ -202,6 +206,9 @@ const App: React.FC = () => {
           onSelectTrack={engine.selectTrack}
           onReorderTracks={engine.reorderTracks}
           onExport={handleExport}
+          onCancelExport={() => exportController.cancelExport()}
+          onSaveProject={() => projectController.saveProject()}
+          onOpenProject={() => projectController.openProject()}
         />
 
         {/* Right Area: Preview Canvas */}
@@ -222,6 +229,7 @@ const App: React.FC = () => {
               settings={settings}
               backgroundImage={backgroundImage}
               analyser={engine.analyser}
+              channelAnalysers={engine.channelAnalysers}
               currentTrack={engine.currentTrack}
               currentTime={engine.currentTime}
               duration={engine.duration}
@@ -20,6 +20,9 @@ import {
   Maximize2,
   ChevronDown,
   BarChart2,
+  Layers,
+  Sparkles,
+  Crosshair,
 } from "lucide-react";
 
 export const Icons = {
@@ -45,4 +48,7 @@ export const Icons = {
   Maximize: Maximize2,
   ChevronDown,
   BarChart2,
+  Spectrogram: Layers,
+  Particles: Sparkles,
+  Goniometer: Crosshair,
 };
@@ -24,6 +24,13 @@ import {
   FontFamily,
   FontSize,
   PRESET_COLORS,
+  BackgroundFit,
+  BackgroundPlayback,
+  BandScale,
+  BarAnchor,
+  BarLayout,
+  Palette,
+  WaveLayout,
 } from "../types";
 import { formatTime } from "../utils";
 
@@ -52,6 +59,9 @@ interface SidebarProps {
   onSelectTrack: (index: number) => void;
   onReorderTracks: (fromIndex: number, toIndex: number) => void;
   onExport: () => void;
+  onCancelExport: () => void;
+  onSaveProject: () => void;
+  onOpenProject: () => void;
 }
 
 type SidebarTab = "media" | "style" | "export";
@@ -146,6 +156,81 @@ const SortableTrackRow: React.FC<SortableTrackRowProps> = ({
   );
 };
 
+interface SliderRowProps {
+  label: string;
+  value: number;
+  min: number;
+  max: number;
+  step: number;
+  format?: (value: number) => string;
+  onChange: (value: number) => void;
+}
+
+/** Labelled range input for a numeric setting */
+const SliderRow: React.FC<SliderRowProps> = ({
+  label,
+  value,
+  min,
+  max,
+  step,
+  format,
+  onChange,
+}) => (
+  <div className="flex items-center gap-3">
+    <span className="w-14 text-[9px] text-zinc-600 font-mono uppercase">{label}</span>
+    <input
+      type="range"
+      min={min}
+      max={max}
+      step={step}
+      value={value}
+      onChange={(e) => onChange(parseFloat(e.target.value))}
+      className="flex-1 h-1 bg-zinc-800 rounded-full appearance-none cursor-pointer accent-plasma hover:accent-amber-400"
+    />
+    <span className="w-10 text-right text-[9px] font-mono text-plasma">
+      {format ? format(value) : value}
+    </span>
+  </div>
+);
+
+interface ChoiceRowProps<T extends string> {
+  label: string;
+  value: T;
+  options: Array<{ label: string; value: T }>;
+  onChange: (value: T) => void;
+}
+
+/** Labelled button group for an enum setting */
+function ChoiceRow<T extends string>({ label, value, options, onChange }: ChoiceRowProps<T>) {
+  return (
+    <div className="flex items-center gap-3">
+      <span className="w-14 text-[9px] text-zinc-600 font-mono uppercase">{label}</span>
+      <div className="flex-1 flex gap-1">
+        {options.map((option) => (
+          <button
+            key={option.value}
+            onClick={() => onChange(option.value)}
+            className={`flex-1 py-1 rounded-sm border text-[9px] font-mono transition-all ${
+              value === option.value
+                ? "bg-white/10 border-plasma text-white"
+                : "bg-black/20 border-white/5 text-zinc-600 hover:bg-white/5"
+            }`}
+          >
+            {option.label}
+          </button>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+const MAX_PALETTE_STOPS = 5;
+
+/** Gradient stops for `colors`, evenly spaced from 0 to 1 */
+function spreadStops(colors: string[]) {
+  return colors.map((color, i) => ({ offset: i / Math.max(1, colors.length - 1), color }));
+}
+
 export const Sidebar: React.FC<SidebarProps> = ({
   isCinemaMode,
   backgroundImage,
@@ -167,6 +252,9 @@ export const Sidebar: React.FC<SidebarProps> = ({
   onSelectTrack,
   onReorderTracks,
   onExport,
+  onCancelExport,
+  onSaveProject,
+  onOpenProject,
 }) => {
   const [activeTab, setActiveTab] = useState<SidebarTab>("media");
   const hasTrack = playlist.length > 0;
@@ -187,7 +275,8 @@ export const Sidebar: React.FC<SidebarProps> = ({
     { label: "Track Title", key: "showTitle" },
     { label: "Progress Bar", key: "showProgress" },
     { label: "Ken Burns", key: "kenBurns" },
-    { label: "Darken BG", key: "blurBackground" },
+    { label: "Blur BG", key: "blurBackground" },
+    { label: "Reactive BG", key: "reactiveBackground" },
   ];
 
   const trackIds = useMemo(() => playlist.map((track) => track.id), [playlist]);
@@ -216,6 +305,20 @@ export const Sidebar: React.FC<SidebarProps> = ({
     dragOrderRef.current = [];
   };
 
+  const palette = settings.visualizerPalette;
+  const paletteColors =
+    palette.kind === "Solid"
+      ? [settings.visualizerColor, "#ffffff"]
+      : palette.stops.map((stop) => stop.color);
+  const setPaletteColors = (colors: string[]) =>
+    setSettings((s) => ({
+      ...s,
+      visualizerPalette:
+        s.visualizerPalette.kind === "Solid"
+          ? s.visualizerPalette
+          : { kind: s.visualizerPalette.kind, stops: spreadStops(colors) },
+    }));
+
   const randomizeVibe = () => {
     const randomColor = PRESET_COLORS[Math.floor(Math.random() * PRESET_COLORS.length)];
     const fonts = Object.values(FontFamily);
@@ -266,7 +369,7 @@ export const Sidebar: React.FC<SidebarProps> = ({
                 {!isDesktopApp && (
                   <input
                     type="file"
-                    accept="image/*"
+                    accept="image/*,video/*"
                     onChange={(e) => onFileUpload(e, "image")}
                     className="absolute inset-0 w-full h-full opacity-0 cursor-pointer z-10"
                   />
@@ -408,6 +511,108 @@ export const Sidebar: React.FC<SidebarProps> = ({
               </div>
             </div>
 
+            {/* Background Fit */}
+            <div className="space-y-2">
+              <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                Background Fit
+              </h3>
+              <div className="grid grid-cols-5 gap-1">
+                {[
+                  { label: "Cover", value: BackgroundFit.Cover },
+                  { label: "Fit", value: BackgroundFit.Contain },
+                  { label: "Blur", value: BackgroundFit.BlurredFill },
+                  { label: "Tile", value: BackgroundFit.Tile },
+                  { label: "Stretch", value: BackgroundFit.Stretch },
+                ].map((fit) => (
+                  <button
+                    key={fit.value}
+                    onClick={() => setSettings((s) => ({ ...s, backgroundFit: fit.value }))}
+                    className={`py-2 rounded-sm border text-[9px] font-mono transition-all ${
+                      settings.backgroundFit === fit.value
+                        ? "bg-white/10 border-plasma text-white"
+                        : "bg-black/20 border-white/5 text-zinc-600 hover:bg-white/5"
+                    }`}
+                  >
+                    {fit.label}
+                  </button>
+                ))}
+              </div>
+
+              {/* Cover crops around the focus, tiling starts from it */}
+              {(settings.backgroundFit === BackgroundFit.Cover ||
+                settings.backgroundFit === BackgroundFit.Tile) &&
+                [
+                  { label: "Focus X", key: "backgroundFocusX" as const },
+                  { label: "Focus Y", key: "backgroundFocusY" as const },
+                ].map((axis) => (
+                  <div key={axis.key} className="flex items-center gap-3">
+                    <span className="w-14 text-[9px] text-zinc-600 font-mono uppercase">
+                      {axis.label}
+                    </span>
+                    <input
+                      type="range"
+                      min="0"
+                      max="1"
+                      step="0.05"
+                      value={settings[axis.key]}
+                      onChange={(e) =>
+                        setSettings((s) => ({ ...s, [axis.key]: parseFloat(e.target.value) }))
+                      }
+                      className="flex-1 h-1 bg-zinc-800 rounded-full appearance-none cursor-pointer accent-plasma hover:accent-amber-400"
+                    />
+                  </div>
+                ))}
+
+              {/* Letterbox / no-image fill: solid, or gradient to a second colour */}
+              <div className="flex items-center gap-2 p-2 bg-black/20 border border-white/5 rounded-sm">
+                <span className="flex-1 text-[9px] text-zinc-600 font-mono uppercase">Fill</span>
+                <input
+                  type="color"
+                  value={settings.backgroundFill}
+                  onChange={(e) => setSettings((s) => ({ ...s, backgroundFill: e.target.value }))}
+                  className="w-6 h-6 rounded-sm overflow-hidden cursor-pointer border-0 bg-transparent p-0"
+                />
+                {settings.backgroundFillBottom !== null && (
+                  <input
+                    type="color"
+                    value={settings.backgroundFillBottom}
+                    onChange={(e) =>
+                      setSettings((s) => ({ ...s, backgroundFillBottom: e.target.value }))
+                    }
+                    className="w-6 h-6 rounded-sm overflow-hidden cursor-pointer border-0 bg-transparent p-0"
+                  />
+                )}
+                <button
+                  onClick={() =>
+                    setSettings((s) => ({
+                      ...s,
+                      backgroundFillBottom: s.backgroundFillBottom === null ? "#000000" : null,
+                    }))
+                  }
+                  className={`px-2 py-1 rounded-sm border text-[9px] font-mono transition-all ${
+                    settings.backgroundFillBottom !== null
+                      ? "bg-white/10 border-plasma text-white"
+                      : "bg-black/20 border-white/5 text-zinc-600 hover:bg-white/5"
+                  }`}
+                >
+                  Gradient
+                </button>
+              </div>
+
+              {/* GIF and video backgrounds; ping-pong previews on videos only */}
+              <ChoiceRow
+                label="Repeat"
+                value={settings.backgroundPlayback}
+                options={[
+                  { label: "Loop", value: BackgroundPlayback.Loop },
+                  { label: "Ping-pong", value: BackgroundPlayback.PingPong },
+                ]}
+                onChange={(backgroundPlayback) =>
+                  setSettings((s) => ({ ...s, backgroundPlayback }))
+                }
+              />
+            </div>
+
             {/* Grid: Mode */}
             <div className="space-y-2">
               <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
@@ -418,6 +623,13 @@ export const Sidebar: React.FC<SidebarProps> = ({
                   { label: "Bars", value: VisualizerMode.Bars, icon: Icons.BarChart2 },
                   { label: "Orbital", value: VisualizerMode.Orbital, icon: Icons.Disc },
                   { label: "Wave", value: VisualizerMode.Wave, icon: Icons.Wave },
+                  {
+                    label: "Spectro",
+                    value: VisualizerMode.Spectrogram,
+                    icon: Icons.Spectrogram,
+                  },
+                  { label: "Particles", value: VisualizerMode.Particles, icon: Icons.Particles },
+                  { label: "Stereo", value: VisualizerMode.Goniometer, icon: Icons.Goniometer },
                 ].map((mode) => (
                   <button
                     key={mode.value}
@@ -435,6 +647,145 @@ export const Sidebar: React.FC<SidebarProps> = ({
               </div>
             </div>
 
+            {/* Bars: band count (Bars and Orbital), row geometry (Bars) */}
+            {(settings.visualizerMode === VisualizerMode.Bars ||
+              settings.visualizerMode === VisualizerMode.Orbital) && (
+              <div className="space-y-2">
+                <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                  Bars
+                </h3>
+                <SliderRow
+                  label="Count"
+                  value={settings.barCount}
+                  min={1}
+                  max={128}
+                  step={1}
+                  onChange={(barCount) => setSettings((s) => ({ ...s, barCount }))}
+                />
+                {settings.visualizerMode === VisualizerMode.Bars && (
+                  <>
+                    <SliderRow
+                      label="Gap"
+                      value={settings.barGapRatio}
+                      min={0}
+                      max={2}
+                      step={0.05}
+                      format={(v) => `${Math.round(v * 100)}%`}
+                      onChange={(barGapRatio) => setSettings((s) => ({ ...s, barGapRatio }))}
+                    />
+                    <ChoiceRow
+                      label="Anchor"
+                      value={settings.barAnchor}
+                      options={[
+                        { label: "BL", value: BarAnchor.BottomLeft },
+                        { label: "BC", value: BarAnchor.BottomCenter },
+                        { label: "BR", value: BarAnchor.BottomRight },
+                        { label: "Top", value: BarAnchor.Top },
+                        { label: "Full", value: BarAnchor.FullWidth },
+                      ]}
+                      onChange={(barAnchor) => setSettings((s) => ({ ...s, barAnchor }))}
+                    />
+                    <ChoiceRow
+                      label="Layout"
+                      value={settings.barLayout}
+                      options={[
+                        { label: "Std", value: BarLayout.Standard },
+                        { label: "Mirror", value: BarLayout.Mirrored },
+                        { label: "Center", value: BarLayout.Centered },
+                        { label: "L/R", value: BarLayout.Stereo },
+                      ]}
+                      onChange={(barLayout) => setSettings((s) => ({ ...s, barLayout }))}
+                    />
+                  </>
+                )}
+              </div>
+            )}
+
+            {settings.visualizerMode === VisualizerMode.Wave && (
+              <div className="space-y-2">
+                <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                  Wave
+                </h3>
+                <ChoiceRow
+                  label="Layout"
+                  value={settings.waveLayout}
+                  options={[
+                    { label: "Mono", value: WaveLayout.Mono },
+                    { label: "L/R", value: WaveLayout.Stereo },
+                  ]}
+                  onChange={(waveLayout) => setSettings((s) => ({ ...s, waveLayout }))}
+                />
+              </div>
+            )}
+
+            {settings.visualizerMode === VisualizerMode.Spectrogram && (
+              <div className="space-y-2">
+                <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                  Spectrogram
+                </h3>
+                <SliderRow
+                  label="History"
+                  value={settings.spectrogramSeconds}
+                  min={1}
+                  max={20}
+                  step={0.5}
+                  format={(v) => `${v}s`}
+                  onChange={(spectrogramSeconds) =>
+                    setSettings((s) => ({ ...s, spectrogramSeconds }))
+                  }
+                />
+              </div>
+            )}
+
+            {settings.visualizerMode === VisualizerMode.Particles && (
+              <div className="space-y-2">
+                <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                  Particles
+                </h3>
+                <SliderRow
+                  label="Density"
+                  value={settings.particleDensity}
+                  min={0.1}
+                  max={4}
+                  step={0.1}
+                  format={(v) => `${v.toFixed(1)}x`}
+                  onChange={(particleDensity) => setSettings((s) => ({ ...s, particleDensity }))}
+                />
+                {/* Seed: the export renders the same burst as the preview */}
+                <div className="flex items-center gap-3">
+                  <span className="w-14 text-[9px] text-zinc-600 font-mono uppercase">Seed</span>
+                  <input
+                    type="number"
+                    min={0}
+                    max={0xffffffff}
+                    step={1}
+                    value={settings.particleSeed}
+                    onChange={(e) =>
+                      setSettings((s) => ({
+                        ...s,
+                        particleSeed: Math.min(
+                          0xffffffff,
+                          Math.max(0, Math.floor(Number(e.target.value)))
+                        ),
+                      }))
+                    }
+                    className="flex-1 min-w-0 bg-black/20 border border-white/10 rounded-sm px-2 py-1 text-[10px] font-mono text-zinc-300 focus:outline-none focus:border-plasma"
+                  />
+                  <button
+                    onClick={() =>
+                      setSettings((s) => ({
+                        ...s,
+                        particleSeed: Math.floor(Math.random() * 0x100000000),
+                      }))
+                    }
+                    className="px-2 py-1 rounded-sm border border-white/5 text-[9px] font-mono text-zinc-600 hover:bg-white/5"
+                  >
+                    New
+                  </button>
+                </div>
+              </div>
+            )}
+
             {/* Color Palette (Hex Grid) */}
             <div className="space-y-2">
               <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
@@ -460,6 +811,60 @@ export const Sidebar: React.FC<SidebarProps> = ({
                   className="w-6 h-6 rounded-sm overflow-hidden cursor-pointer border-0 bg-transparent p-0 opacity-50 hover:opacity-100"
                 />
               </div>
+
+              {/* Palette: solid accent, or a gradient across the visualizer */}
+              <ChoiceRow<Palette["kind"]>
+                label="Palette"
+                value={palette.kind}
+                options={[
+                  { label: "Solid", value: "Solid" },
+                  { label: "Vert", value: "Vertical" },
+                  { label: "Horiz", value: "Horizontal" },
+                  { label: "Radial", value: "Radial" },
+                  { label: "Level", value: "Amplitude" },
+                ]}
+                onChange={(kind) =>
+                  setSettings((s) => ({
+                    ...s,
+                    visualizerPalette:
+                      kind === "Solid" ? { kind } : { kind, stops: spreadStops(paletteColors) },
+                  }))
+                }
+              />
+              {palette.kind !== "Solid" && (
+                <div className="flex items-center gap-2 p-2 bg-black/20 border border-white/5 rounded-sm">
+                  {paletteColors.map((color, i) => (
+                    <input
+                      key={i}
+                      type="color"
+                      value={color}
+                      onChange={(e) =>
+                        setPaletteColors(
+                          paletteColors.map((c, j) => (j === i ? e.target.value : c))
+                        )
+                      }
+                      className="w-6 h-6 rounded-sm overflow-hidden cursor-pointer border-0 bg-transparent p-0"
+                    />
+                  ))}
+                  <div className="flex-1" />
+                  <button
+                    onClick={() => setPaletteColors(paletteColors.slice(0, -1))}
+                    disabled={paletteColors.length <= 2}
+                    className="px-2 py-1 rounded-sm border border-white/5 text-[9px] font-mono text-zinc-600 hover:bg-white/5 disabled:opacity-30"
+                  >
+                    -
+                  </button>
+                  <button
+                    onClick={() =>
+                      setPaletteColors([...paletteColors, paletteColors[paletteColors.length - 1]])
+                    }
+                    disabled={paletteColors.length >= MAX_PALETTE_STOPS}
+                    className="px-2 py-1 rounded-sm border border-white/5 text-[9px] font-mono text-zinc-600 hover:bg-white/5 disabled:opacity-30"
+                  >
+                    +
+                  </button>
+                </div>
+              )}
             </div>
 
             {/* Typography Dropdown */}
@@ -544,6 +949,152 @@ export const Sidebar: React.FC<SidebarProps> = ({
               </div>
             </div>
 
+            {/* Blur BG look: preview filter and export use the same numbers */}
+            {settings.blurBackground && (
+              <div className="space-y-2">
+                <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                  Background Look
+                </h3>
+                <SliderRow
+                  label="Blur"
+                  value={settings.backgroundBlur}
+                  min={0}
+                  max={40}
+                  step={1}
+                  format={(v) => `${v}px`}
+                  onChange={(backgroundBlur) => setSettings((s) => ({ ...s, backgroundBlur }))}
+                />
+                <SliderRow
+                  label="Dim"
+                  value={settings.backgroundDim}
+                  min={0}
+                  max={0.9}
+                  step={0.05}
+                  format={(v) => `${Math.round(v * 100)}%`}
+                  onChange={(backgroundDim) => setSettings((s) => ({ ...s, backgroundDim }))}
+                />
+                <SliderRow
+                  label="Desat"
+                  value={settings.backgroundDesaturate}
+                  min={0}
+                  max={1}
+                  step={0.05}
+                  format={(v) => `${Math.round(v * 100)}%`}
+                  onChange={(backgroundDesaturate) =>
+                    setSettings((s) => ({ ...s, backgroundDesaturate }))
+                  }
+                />
+              </div>
+            )}
+
+            {/* Reactive BG strengths, 0 turns an effect off */}
+            {settings.reactiveBackground && (
+              <div className="space-y-2">
+                <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                  Reactive BG
+                </h3>
+                <SliderRow
+                  label="Zoom"
+                  value={settings.reactiveZoomPulse}
+                  min={0}
+                  max={1}
+                  step={0.05}
+                  format={(v) => `${Math.round(v * 100)}%`}
+                  onChange={(reactiveZoomPulse) =>
+                    setSettings((s) => ({ ...s, reactiveZoomPulse }))
+                  }
+                />
+                <SliderRow
+                  label="Pump"
+                  value={settings.reactiveBrightnessPump}
+                  min={0}
+                  max={1}
+                  step={0.05}
+                  format={(v) => `${Math.round(v * 100)}%`}
+                  onChange={(reactiveBrightnessPump) =>
+                    setSettings((s) => ({ ...s, reactiveBrightnessPump }))
+                  }
+                />
+                <SliderRow
+                  label="Split"
+                  value={settings.reactiveChromatic}
+                  min={0}
+                  max={1}
+                  step={0.05}
+                  format={(v) => `${Math.round(v * 100)}%`}
+                  onChange={(reactiveChromatic) =>
+                    setSettings((s) => ({ ...s, reactiveChromatic }))
+                  }
+                />
+                <SliderRow
+                  label="Shake"
+                  value={settings.reactiveShake}
+                  min={0}
+                  max={1}
+                  step={0.05}
+                  format={(v) => `${Math.round(v * 100)}%`}
+                  onChange={(reactiveShake) => setSettings((s) => ({ ...s, reactiveShake }))}
+                />
+              </div>
+            )}
+
+            {/* Ken Burns motion: preview and export move the same way */}
+            {settings.kenBurns && (
+              <div className="space-y-2">
+                <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                  Ken Burns
+                </h3>
+                <SliderRow
+                  label="Speed"
+                  value={settings.kenBurnsSpeed}
+                  min={0.25}
+                  max={8}
+                  step={0.25}
+                  format={(v) => `${v}x`}
+                  onChange={(kenBurnsSpeed) => setSettings((s) => ({ ...s, kenBurnsSpeed }))}
+                />
+                <SliderRow
+                  label="Zoom Min"
+                  value={settings.kenBurnsZoomMin}
+                  min={1}
+                  max={1.5}
+                  step={0.01}
+                  format={(v) => `${v.toFixed(2)}x`}
+                  onChange={(kenBurnsZoomMin) =>
+                    setSettings((s) => ({
+                      ...s,
+                      kenBurnsZoomMin,
+                      kenBurnsZoomMax: Math.max(s.kenBurnsZoomMax, kenBurnsZoomMin),
+                    }))
+                  }
+                />
+                <SliderRow
+                  label="Zoom Max"
+                  value={settings.kenBurnsZoomMax}
+                  min={1}
+                  max={1.5}
+                  step={0.01}
+                  format={(v) => `${v.toFixed(2)}x`}
+                  onChange={(kenBurnsZoomMax) =>
+                    setSettings((s) => ({
+                      ...s,
+                      kenBurnsZoomMax,
+                      kenBurnsZoomMin: Math.min(s.kenBurnsZoomMin, kenBurnsZoomMax),
+                    }))
+                  }
+                />
+                {/* Seed bits: flip horizontal pan, vertical pan, zoom direction */}
+                <SliderRow
+                  label="Direction"
+                  value={settings.kenBurnsSeed}
+                  min={0}
+                  max={7}
+                  step={1}
+                  onChange={(kenBurnsSeed) => setSettings((s) => ({ ...s, kenBurnsSeed }))}
+                />
+              </div>
+            )}
+
             {/* Intensity Slider */}
             <div className="space-y-2">
               <div className="flex justify-between">
@@ -565,6 +1116,139 @@ export const Sidebar: React.FC<SidebarProps> = ({
                 }
                 className="w-full h-1 bg-zinc-800 rounded-full appearance-none cursor-pointer accent-plasma hover:accent-amber-400"
               />
+              {/* Automatic gain: levels quiet and loud masters, on top of Gain */}
+              <ChoiceRow
+                label="Auto"
+                value={settings.autoGain ? "on" : "off"}
+                options={[
+                  { label: "Off", value: "off" },
+                  { label: "On", value: "on" },
+                ]}
+                onChange={(v) => setSettings((s) => ({ ...s, autoGain: v === "on" }))}
+              />
+              {settings.autoGain && (
+                <>
+                  <SliderRow
+                    label="Range"
+                    value={settings.autoGainMaxDb}
+                    min={3}
+                    max={48}
+                    step={1}
+                    format={(v) => `${v}dB`}
+                    onChange={(autoGainMaxDb) => setSettings((s) => ({ ...s, autoGainMaxDb }))}
+                  />
+                  <SliderRow
+                    label="Release"
+                    value={settings.autoGainReleaseSecs}
+                    min={0.5}
+                    max={20}
+                    step={0.5}
+                    format={(v) => `${v}s`}
+                    onChange={(autoGainReleaseSecs) =>
+                      setSettings((s) => ({ ...s, autoGainReleaseSecs }))
+                    }
+                  />
+                </>
+              )}
+            </div>
+
+            {/* Response: how fast levels follow the audio */}
+            <div className="space-y-2">
+              <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                Response
+              </h3>
+              <SliderRow
+                label="Attack"
+                value={settings.attackSecs}
+                min={0.002}
+                max={0.2}
+                step={0.002}
+                format={(v) => `${Math.round(v * 1000)}ms`}
+                onChange={(attackSecs) => setSettings((s) => ({ ...s, attackSecs }))}
+              />
+              <SliderRow
+                label="Decay"
+                value={settings.decaySecs}
+                min={0.02}
+                max={1}
+                step={0.01}
+                format={(v) => `${Math.round(v * 1000)}ms`}
+                onChange={(decaySecs) => setSettings((s) => ({ ...s, decaySecs }))}
+              />
+            </div>
+
+            {/* Spectrum: band spacing and frequency range */}
+            <div className="space-y-2">
+              <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                Spectrum
+              </h3>
+              <ChoiceRow
+                label="Scale"
+                value={settings.bandScale}
+                options={[
+                  { label: "Lin", value: BandScale.Linear },
+                  { label: "Log", value: BandScale.Log },
+                  { label: "Mel", value: BandScale.Mel },
+                  { label: "Bark", value: BandScale.Bark },
+                ]}
+                onChange={(bandScale) => setSettings((s) => ({ ...s, bandScale }))}
+              />
+              <SliderRow
+                label="Low"
+                value={settings.bandMinHz}
+                min={20}
+                max={1000}
+                step={10}
+                format={(v) => `${v}Hz`}
+                onChange={(bandMinHz) => setSettings((s) => ({ ...s, bandMinHz }))}
+              />
+              <SliderRow
+                label="High"
+                value={settings.bandMaxHz}
+                min={2000}
+                max={22000}
+                step={500}
+                format={(v) => `${(v / 1000).toFixed(1)}k`}
+                onChange={(bandMaxHz) => setSettings((s) => ({ ...s, bandMaxHz }))}
+              />
+            </div>
+
+            {/* Glow: bloom over the visualizer; radius and threshold only matter when on */}
+            <div className="space-y-2">
+              <h3 className="text-[10px] font-bold text-zinc-500 uppercase tracking-widest">
+                Glow
+              </h3>
+              <SliderRow
+                label="Amount"
+                value={settings.glowStrength}
+                min={0}
+                max={2}
+                step={0.05}
+                format={(v) => (v > 0 ? `${Math.round(v * 100)}%` : "Off")}
+                onChange={(glowStrength) => setSettings((s) => ({ ...s, glowStrength }))}
+              />
+              {settings.glowStrength > 0 && (
+                <>
+                  <SliderRow
+                    label="Radius"
+                    value={settings.glowRadius}
+                    min={2}
+                    max={64}
+                    step={1}
+                    format={(v) => `${v}px`}
+                    onChange={(glowRadius) => setSettings((s) => ({ ...s, glowRadius }))}
+                  />
+                  <SliderRow
+                    label="Thresh"
+                    value={settings.glowThreshold}
+                    min={0}
+                    max={1}
+                    step={0.05}
+                    format={(v) => v.toFixed(2)}
+                    onChange={(glowThreshold) => setSettings((s) => ({ ...s, glowThreshold }))}
+                  />
+                </>
+              )}
             </div>
           </div>
         )}
@@ -660,7 +1344,34 @@ export const Sidebar: React.FC<SidebarProps> = ({
                   ? "Rendering..."
                   : "Export Video (.mp4)"}
             </button>
+            {isExporting && (
+              <button
+                onClick={onCancelExport}
+                className="btn-mechanical w-full py-2 text-[10px] text-zinc-400 hover:text-red-400"
+              >
+                Cancel Export
+              </button>
+            )}
             <div className="text-[9px] text-zinc-600 font-mono uppercase">{exportHint}</div>
+
+            {isDesktopApp && (
+              <div className="w-full grid grid-cols-2 gap-2">
+                <button
+                  onClick={onOpenProject}
+                  disabled={isExporting}
+                  className="btn-mechanical py-2 text-[10px] disabled:opacity-50 disabled:cursor-not-allowed"
+                >
+                  Open Project
+                </button>
+                <button
+                  onClick={onSaveProject}
+                  disabled={isExporting || !hasTrack}
+                  className="btn-mechanical py-2 text-[10px] disabled:opacity-50 disabled:cursor-not-allowed"
+                >
+                  Save Project
+                </button>
+              </div>
+            )}
           </div>
         )}
       </div>
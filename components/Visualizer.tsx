@@ -1,11 +1,12 @@
 import React, { useRef, useEffect } from "react";
-import { VibeSettings, Track, AspectRatio } from "../types";
+import { VibeSettings, Track, AspectRatio, BackgroundPlayback } from "../types";
 import { VisualizerCore } from "./VisualizerCore";
 
 interface VisualizerProps {
   settings: VibeSettings;
   backgroundImage: string | null;
   analyser: AnalyserNode | null;
+  channelAnalysers: [AnalyserNode, AnalyserNode] | null;
   currentTrack: Track | null;
   currentTime: number;
   duration: number;
@@ -16,6 +17,7 @@ const Visualizer: React.FC<VisualizerProps> = ({
   settings,
   backgroundImage,
   analyser,
+  channelAnalysers,
   currentTrack,
   currentTime,
   duration,
@@ -23,7 +25,7 @@ const Visualizer: React.FC<VisualizerProps> = ({
 }) => {
   const canvasRef = useRef<HTMLCanvasElement>(null);
   const requestRef = useRef<number>();
-  const imageRef = useRef<HTMLImageElement | null>(null);
+  const imageRef = useRef<HTMLImageElement | HTMLVideoElement | null>(null);
 
   // Core Engine Ref
   const coreRef = useRef<VisualizerCore>(new VisualizerCore());
@@ -41,18 +43,60 @@ const Visualizer: React.FC<VisualizerProps> = ({
     isPlayingRef.current = isPlaying;
   }, [currentTime, duration, isPlaying]);
 
-  // Load Image
+  // Load Image; anything the browser can't decode as one is tried as a
+  // muted video, repeated like the export (which decodes it with FFmpeg)
+  const pingPong = settings.backgroundPlayback === BackgroundPlayback.PingPong;
   useEffect(() => {
-    if (backgroundImage) {
-      const img = new Image();
-      img.src = backgroundImage;
-      img.onload = () => {
-        imageRef.current = img;
-      };
-    } else {
+    if (!backgroundImage) {
       imageRef.current = null;
+      return;
     }
-  }, [backgroundImage]);
+    let video: HTMLVideoElement | null = null;
+    let rewindFrame = 0;
+    const img = new Image();
+    img.src = backgroundImage;
+    img.onload = () => {
+      imageRef.current = img;
+    };
+    img.onerror = () => {
+      const v = document.createElement("video");
+      video = v;
+      v.muted = true;
+      v.loop = !pingPong;
+      v.playsInline = true;
+      v.src = backgroundImage;
+      v.onloadeddata = () => {
+        imageRef.current = v;
+        void v.play();
+      };
+      // Browsers won't play backwards; step back by hand, then play on
+      v.onended = () => {
+        const end = v.currentTime;
+        const start = performance.now();
+        const rewind = (now: number) => {
+          const t = end - (now - start) / 1000;
+          if (t <= 0) {
+            v.currentTime = 0;
+            void v.play();
+            return;
+          }
+          v.currentTime = t;
+          rewindFrame = requestAnimationFrame(rewind);
+        };
+        rewindFrame = requestAnimationFrame(rewind);
+      };
+    };
+    return () => {
+      img.onload = null;
+      img.onerror = null;
+      cancelAnimationFrame(rewindFrame);
+      if (video) {
+        video.onloadeddata = null;
+        video.onended = null;
+        video.pause();
+      }
+    };
+  }, [backgroundImage, pingPong]);
 
   const draw = (_timestamp: number) => {
     const canvas = canvasRef.current;
@@ -77,14 +121,23 @@ const Visualizer: React.FC<VisualizerProps> = ({
       canvas.height = height;
     }
 
-    // 2. Audio Data
-    let bufferLen = 0;
-    let frequencyData = new Uint8Array(0);
-
-    if (analyser) {
-      bufferLen = analyser.frequencyBinCount;
-      frequencyData = new Uint8Array(bufferLen);
-      analyser.getByteFrequencyData(frequencyData);
+    // 2. Audio Data (raw PCM; the engine runs its own FFT, shared with export)
+    // Left/right when the channel taps exist, otherwise the mix on both sides
+    let left = new Float32Array(0);
+    let right = left;
+    let sampleRate = 44100;
+
+    if (channelAnalysers) {
+      const [leftAnalyser, rightAnalyser] = channelAnalysers;
+      left = new Float32Array(leftAnalyser.fftSize);
+      right = new Float32Array(rightAnalyser.fftSize);
+      leftAnalyser.getFloatTimeDomainData(left);
+      rightAnalyser.getFloatTimeDomainData(right);
+      sampleRate = leftAnalyser.context.sampleRate;
+    } else if (analyser) {
+      left = right = new Float32Array(analyser.fftSize);
+      analyser.getFloatTimeDomainData(left);
+      sampleRate = analyser.context.sampleRate;
     }
 
     // 3. Delegate to Core
@@ -96,7 +149,9 @@ const Visualizer: React.FC<VisualizerProps> = ({
         width,
         height,
         settings,
-        frequencyData,
+        left,
+        right,
+        sampleRate,
         imageRef.current,
         currentTrack,
         currentTimeRef.current,
@@ -116,7 +171,7 @@ const Visualizer: React.FC<VisualizerProps> = ({
     return () => {
       if (requestRef.current) cancelAnimationFrame(requestRef.current);
     };
-  }, [settings, backgroundImage, analyser, currentTrack]);
+  }, [settings, backgroundImage, analyser, channelAnalysers, currentTrack]);
 
   return (
     <div className="w-full h-full flex items-center justify-center bg-zinc-950 overflow-hidden relative shadow-2xl rounded-lg border border-zinc-800">
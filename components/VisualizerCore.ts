@@ -1,10 +1,17 @@
-import { VibeSettings, Track } from "../types";
+import { VibeSettings, Track, BackgroundFit } from "../types";
 import init, { VibeEngine } from "../src/vibe-engine-wasm";
 import { drawTitleArtist } from "../utils/overlayText";
+import { kenBurnsOptions, kenBurnsTransform } from "../utils/kenBurns";
+import { backgroundFilter, backgroundStyle } from "../utils/backgroundStyle";
+import { BASS_HZ, reactiveStrengths, reactiveView } from "../utils/reactiveBackground";
+import { drawFitted, fitSize, focusOffset, paintFill } from "../utils/backgroundFit";
+import { toRustSettings } from "../utils/engineSettings";
 
 // Initialize WASM once; capture exports for memory access.
 const wasmExports = await init();
 
+type Ctx = CanvasRenderingContext2D | OffscreenCanvasRenderingContext2D;
+
 export class VisualizerCore {
   private overlayCanvas: HTMLCanvasElement;
   private overlayCtx: CanvasRenderingContext2D | null;
@@ -12,6 +19,14 @@ export class VisualizerCore {
   private height = 0;
   private imageData?: ImageData;
   private engine: VibeEngine;
+  /** Ken Burns clock: advances only while playing, so pausing freezes it */
+  private kenBurnsTime = 0;
+  private lastElapsed = 0;
+  /** Engine bass level and beat pulse from the previous frame */
+  private bass = 0;
+  private beat = 0;
+  /** Scratch layers for the chromatic split: one channel pass, their sum */
+  private splitCanvases: [HTMLCanvasElement, HTMLCanvasElement] | null = null;
 
   constructor() {
     this.engine = VibeEngine.new(100, 100);
@@ -19,12 +34,73 @@ export class VisualizerCore {
     this.overlayCtx = this.overlayCanvas.getContext("2d", { willReadFrequently: true });
   }
 
+  /**
+   * Chromatic aberration: the background drawn once per channel, red at
+   * `scale * (1 + aberration)` and blue at `scale * (1 - aberration)`, like
+   * the export's per-channel sampling. Each pass starts from the fill
+   * already on `ctx`, so letterboxed modes split the same way.
+   */
+  private drawSplit(
+    ctx: Ctx,
+    width: number,
+    height: number,
+    scale: number,
+    aberration: number,
+    paint: (target: Ctx, scale: number) => void
+  ) {
+    if (!this.splitCanvases) {
+      this.splitCanvases = [document.createElement("canvas"), document.createElement("canvas")];
+    }
+    const [passCanvas, sumCanvas] = this.splitCanvases;
+    for (const canvas of this.splitCanvases) {
+      if (canvas.width !== width || canvas.height !== height) {
+        canvas.width = width;
+        canvas.height = height;
+      }
+    }
+    const pass = passCanvas.getContext("2d");
+    const sum = sumCanvas.getContext("2d");
+    if (!pass || !sum) {
+      paint(ctx, scale);
+      return;
+    }
+
+    sum.globalCompositeOperation = "copy";
+    sum.fillStyle = "#000";
+    sum.fillRect(0, 0, width, height);
+    sum.globalCompositeOperation = "lighter";
+    const channels: [number, string][] = [
+      [1 + aberration, "#f00"],
+      [1, "#0f0"],
+      [1 - aberration, "#00f"],
+    ];
+    for (const [k, mask] of channels) {
+      pass.globalCompositeOperation = "copy";
+      pass.drawImage(ctx.canvas, 0, 0, width, height);
+      pass.globalCompositeOperation = "source-over";
+      paint(pass, scale * k);
+      // Keep one channel, then add it into the sum
+      pass.globalCompositeOperation = "multiply";
+      pass.fillStyle = mask;
+      pass.fillRect(0, 0, width, height);
+      sum.drawImage(passCanvas, 0, 0);
+    }
+
+    ctx.save();
+    ctx.setTransform(1, 0, 0, 1, 0, 0);
+    ctx.globalCompositeOperation = "copy";
+    ctx.drawImage(sumCanvas, 0, 0);
+    ctx.restore();
+  }
+
   public render(
     ctx: CanvasRenderingContext2D | OffscreenCanvasRenderingContext2D,
     width: number,
     height: number,
     settings: VibeSettings,
-    frequencyData: Uint8Array,
+    left: Float32Array,
+    right: Float32Array,
+    sampleRate: number,
     backgroundImage: CanvasImageSource | null,
     currentTrack: Track | null,
     currentTime: number,
@@ -43,41 +119,98 @@ export class VisualizerCore {
     }
 
     // 2. Draw Background (JS)
-    ctx.fillStyle = "#030304";
-    ctx.fillRect(0, 0, width, height);
+    paintFill(ctx, settings, 0, 0, width, height);
+
+    if (isPlaying) {
+      this.kenBurnsTime += Math.max(0, elapsedTime - this.lastElapsed);
+    }
+    this.lastElapsed = elapsedTime;
 
     if (backgroundImage) {
       ctx.save();
-      let scale = 1;
-      let tx = 0,
-        ty = 0;
-
-      if (settings.kenBurns && isPlaying) {
-        const t = (elapsedTime * 1000) / 20000;
-        scale = 1.05 + Math.sin(t) * 0.02;
-        tx = Math.cos(t * 0.5) * 15;
-        ty = Math.sin(t * 0.3) * 15;
-      }
 
       const img = backgroundImage as any;
       const imgWidth = img.videoWidth || img.width || width;
       const imgHeight = img.videoHeight || img.height || height;
 
-      const r = width / height;
-      const ir = imgWidth / imgHeight;
-      let dw, dh;
-      if (ir > r) {
-        dh = height;
-        dw = height * ir;
-      } else {
-        dw = width;
-        dh = width / ir;
+      // Cover: the image's cover size. Other fit modes lay the image out in
+      // a frame-shaped rect that moves and zooms as a whole.
+      const cover = settings.backgroundFit === BackgroundFit.Cover;
+      let { w: dw, h: dh } = cover
+        ? fitSize(BackgroundFit.Cover, width, height, imgWidth, imgHeight)
+        : { w: width, h: height };
+
+      let { scale, tx, ty } = settings.kenBurns
+        ? kenBurnsTransform(this.kenBurnsTime, width, height, dw, dh, kenBurnsOptions(settings))
+        : { scale: 1, tx: 0, ty: 0 };
+
+      let aberration = 0;
+      const filters: string[] = [];
+      if (settings.blurBackground) {
+        // Blur reads transparent pixels past the image edge; overdraw by
+        // ~3 sigma so the frame edges don't darken (export clamps instead)
+        const style = backgroundStyle(settings);
+        const pad = (style.blur * Math.min(width, height) * 3) / 1080;
+        const grow = 1 + (pad * 2) / Math.min(dw, dh);
+        dw *= grow;
+        dh *= grow;
+        filters.push(backgroundFilter(style, width, height));
+      }
+
+      if (settings.reactiveBackground) {
+        const view = reactiveView(
+          reactiveStrengths(settings),
+          this.bass,
+          this.beat,
+          elapsedTime,
+          Math.min(width, height)
+        );
+        scale *= view.zoom;
+        tx += view.tx;
+        ty += view.ty;
+        filters.push(`brightness(${view.brightness})`);
+        aberration = view.aberration;
       }
 
-      ctx.translate(width / 2 + tx, height / 2 + ty);
-      ctx.scale(scale, scale);
+      if (cover) {
+        tx += focusOffset(settings.backgroundFocusX, dw, width, scale);
+        ty += focusOffset(settings.backgroundFocusY, dh, height, scale);
+      }
+      const maxTx = Math.max(0, (dw * scale - width) / 2);
+      const maxTy = Math.max(0, (dh * scale - height) / 2);
+      tx = Math.min(maxTx, Math.max(-maxTx, tx));
+      ty = Math.min(maxTy, Math.max(-maxTy, ty));
+
+      const paint = (target: Ctx, s: number) => {
+        target.save();
+        if (filters.length > 0) {
+          target.filter = filters.join(" ");
+        }
+        target.translate(width / 2 + tx, height / 2 + ty);
+        target.scale(s, s);
+        try {
+          drawFitted(
+            target,
+            backgroundImage,
+            settings,
+            dw,
+            dh,
+            imgWidth,
+            imgHeight,
+            Math.min(width, height)
+          );
+        } finally {
+          target.restore();
+        }
+      };
+
       try {
-        ctx.drawImage(backgroundImage, -dw / 2, -dh / 2, dw, dh);
+        // Under half a pixel of split at the frame edge is invisible
+        if (aberration * Math.min(width, height) < 1) {
+          paint(ctx, scale);
+        } else {
+          this.drawSplit(ctx, width, height, scale, aberration, paint);
+        }
       } catch (e) {
         console.warn("BG Draw failed", e);
       }
@@ -93,10 +226,13 @@ export class VisualizerCore {
       ctx.fillRect(0, height - gradientHeight, width, gradientHeight);
     }
 
-    // 3. Render Bars (Rust)
+    // 3. Render Visualizer (Rust)
     try {
       const wasmSettings = this.mapSettings(settings);
-      this.engine.render(wasmSettings, frequencyData, elapsedTime);
+      this.engine.set_sample_rate(sampleRate);
+      this.engine.render_stereo(wasmSettings, left, right, elapsedTime);
+      this.bass = this.engine.band_energy(BASS_HZ[0], BASS_HZ[1]);
+      this.beat = this.engine.beat_strength();
     } catch (e) {
       console.error("WASM Render Error:", e);
       return;
@@ -125,11 +261,7 @@ export class VisualizerCore {
 
   private mapSettings(settings: VibeSettings) {
     // Pull complexity down: adapt camelCase TS settings to WASM snake_case
-    return {
-      visualizer_mode: settings.visualizerMode,
-      visualizer_color: settings.visualizerColor,
-      visualizer_intensity: settings.visualizerIntensity,
-    };
+    return toRustSettings(settings);
   }
 
   private drawOverlays(
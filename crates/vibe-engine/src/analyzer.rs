@@ -0,0 +1,282 @@
+//! Spectrum analysis from raw PCM.
+//!
+//! Mirrors Web Audio's AnalyserNode (Blackman window, magnitude / N,
+//! smoothing, dB to byte with the default -100..-30 dB range) so the numbers
+//! look like what the preview used to read from `getByteFrequencyData`. Both
+//! preview (WASM) and export (native) run this same code, so a frame with the
+//! same samples and timing produces the same spectrum everywhere.
+//!
+//! Smoothing is a time constant rather than a per-call factor; AnalyserNode's
+//! 0.8 per call depends on how often it is polled.
+//!
+//! Optional automatic gain shifts the dB window so the rolling spectral peak
+//! sits near the top: quiet recordings fill the range, hot masters stop
+//! pinning it. Peak-following rather than whole-track loudness, so the
+//! preview and export behave the same.
+
+use std::f32::consts::TAU;
+
+use crate::smoothing_factor;
+
+/// Samples per analysis window (AnalyserNode `fftSize`).
+pub const FFT_SIZE: usize = 2048;
+
+const MIN_DECIBELS: f32 = -100.0;
+const MAX_DECIBELS: f32 = -30.0;
+/// Equivalent to `smoothingTimeConstant = 0.8` polled at 60fps.
+const SMOOTHING_SECS: f32 = 0.0747;
+/// Where automatic gain puts the rolling peak (dB), just under the top.
+const AGC_TARGET_DB: f32 = MAX_DECIBELS - 3.0;
+/// How fast the rolling peak rises to a louder passage (seconds).
+const AGC_ATTACK_SECS: f32 = 0.25;
+/// Peaks below this (dB) are treated as silence: gain holds instead of
+/// climbing toward the noise floor.
+const AGC_GATE_DB: f32 = -85.0;
+
+/// Automatic gain settings for one frame.
+pub struct AutoGain {
+    /// Largest boost or cut applied (dB).
+    pub max_db: f32,
+    /// How fast the rolling peak falls after a loud passage (seconds).
+    pub release_secs: f32,
+}
+
+pub struct Analyzer {
+    window: Vec<f32>,
+    /// `e^(-2πik/N)` for k in 0..N/2.
+    twiddles: Vec<(f32, f32)>,
+    bit_reverse: Vec<usize>,
+    re: Vec<f32>,
+    im: Vec<f32>,
+    /// Smoothed linear magnitudes, one per bin.
+    smoothed: Vec<f32>,
+    bytes: Vec<u8>,
+    /// Rolling spectral peak (dB); `None` until audio has been heard.
+    peak_db: Option<f32>,
+    /// Gain applied by the last `analyze` (dB).
+    gain_db: f32,
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::new(FFT_SIZE)
+    }
+}
+
+impl Analyzer {
+    /// `size` must be a power of two.
+    pub fn new(size: usize) -> Self {
+        assert!(size.is_power_of_two() && size >= 2, "FFT size must be a power of two");
+        let n = size as f32;
+
+        // Blackman, alpha = 0.16 (as specified for AnalyserNode)
+        let window = (0..size)
+            .map(|i| {
+                let x = i as f32 / n;
+                0.42 - 0.5 * (TAU * x).cos() + 0.08 * (2.0 * TAU * x).cos()
+            })
+            .collect();
+        let twiddles = (0..size / 2)
+            .map(|k| {
+                let angle = -TAU * k as f32 / n;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        let bits = size.trailing_zeros();
+        let bit_reverse = (0..size)
+            .map(|i| i.reverse_bits() >> (usize::BITS - bits))
+            .collect();
+
+        Self {
+            window,
+            twiddles,
+            bit_reverse,
+            re: vec![0.0; size],
+            im: vec![0.0; size],
+            smoothed: vec![0.0; size / 2],
+            bytes: vec![0; size / 2],
+            peak_db: None,
+            gain_db: 0.0,
+        }
+    }
+
+    /// Byte spectrum (`size / 2` bins, DC first) of the latest samples.
+    /// `pcm` is the most recent audio in -1.0..1.0, newest last; shorter input
+    /// is treated as silence before it. `dt` is seconds since the last call.
+    pub fn analyze(&mut self, pcm: &[f32], dt: f32, auto_gain: Option<&AutoGain>) -> &[u8] {
+        let peak = self.transform(pcm, dt);
+        self.gain_db = match auto_gain {
+            Some(agc) => self.track_peak(to_db(peak), dt, agc),
+            None => 0.0,
+        };
+        self.quantize();
+        &self.bytes
+    }
+
+    /// `analyze` with a gain chosen elsewhere, so related signals (stereo
+    /// channels following the mid) stay on one scale.
+    pub fn analyze_at_gain(&mut self, pcm: &[f32], dt: f32, gain_db: f32) -> &[u8] {
+        self.transform(pcm, dt);
+        self.gain_db = gain_db;
+        self.quantize();
+        &self.bytes
+    }
+
+    /// The spectrum from the last `analyze`.
+    pub fn spectrum(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn gain_db(&self) -> f32 {
+        self.gain_db
+    }
+
+    /// Window, FFT and smooth into `smoothed`; returns the peak magnitude.
+    fn transform(&mut self, pcm: &[f32], dt: f32) -> f32 {
+        let size = self.window.len();
+        let recent = &pcm[pcm.len().saturating_sub(size)..];
+        let pad = size - recent.len();
+
+        self.re[..pad].fill(0.0);
+        for (i, &s) in recent.iter().enumerate() {
+            self.re[pad + i] = s * self.window[pad + i];
+        }
+        self.im.fill(0.0);
+        self.fft();
+
+        let k = smoothing_factor(dt, SMOOTHING_SECS);
+        let scale = 1.0 / size as f32;
+        let mut peak = 0.0f32;
+        for (bin, smoothed) in self.smoothed.iter_mut().enumerate() {
+            let magnitude = self.re[bin].hypot(self.im[bin]) * scale;
+            *smoothed += (magnitude - *smoothed) * k;
+            peak = peak.max(*smoothed);
+        }
+        peak
+    }
+
+    /// dB window (shifted by `gain_db`) to bytes.
+    fn quantize(&mut self) {
+        let range = MAX_DECIBELS - MIN_DECIBELS;
+        for (smoothed, byte) in self.smoothed.iter().zip(&mut self.bytes) {
+            let db = to_db(*smoothed) + self.gain_db;
+            *byte = (255.0 * (db - MIN_DECIBELS) / range).clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    /// Follow the frame's peak and return the gain (dB) that puts it at
+    /// `AGC_TARGET_DB`.
+    fn track_peak(&mut self, frame_db: f32, dt: f32, agc: &AutoGain) -> f32 {
+        let rolling = match self.peak_db {
+            _ if frame_db < AGC_GATE_DB => self.peak_db,
+            None => Some(frame_db),
+            Some(current) => {
+                let tau = if frame_db > current { AGC_ATTACK_SECS } else { agc.release_secs };
+                Some(current + (frame_db - current) * smoothing_factor(dt, tau))
+            }
+        };
+        self.peak_db = rolling;
+
+        let max = agc.max_db.max(0.0);
+        rolling.map_or(0.0, |p| (AGC_TARGET_DB - p).clamp(-max, max))
+    }
+
+    /// In-place iterative radix-2 FFT over `re`/`im`.
+    fn fft(&mut self) {
+        let size = self.re.len();
+        for (i, &j) in self.bit_reverse.iter().enumerate() {
+            if i < j {
+                self.re.swap(i, j);
+                self.im.swap(i, j);
+            }
+        }
+
+        let mut len = 2;
+        while len <= size {
+            let half = len / 2;
+            let stride = size / len;
+            for start in (0..size).step_by(len) {
+                for k in 0..half {
+                    let (wr, wi) = self.twiddles[k * stride];
+                    let (a, b) = (start + k, start + k + half);
+                    let tr = self.re[b] * wr - self.im[b] * wi;
+                    let ti = self.re[b] * wi + self.im[b] * wr;
+                    self.re[b] = self.re[a] - tr;
+                    self.im[b] = self.im[a] - ti;
+                    self.re[a] += tr;
+                    self.im[a] += ti;
+                }
+            }
+            len *= 2;
+        }
+    }
+}
+
+fn to_db(magnitude: f32) -> f32 {
+    20.0 * magnitude.max(1e-10).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: f32 = 48_000.0;
+
+    fn tone(hz: f32, amplitude: f32) -> Vec<f32> {
+        (0..FFT_SIZE).map(|i| (TAU * hz * i as f32 / RATE).sin() * amplitude).collect()
+    }
+
+    fn settled(analyzer: &mut Analyzer, pcm: &[f32]) -> Vec<u8> {
+        for _ in 0..60 {
+            analyzer.analyze(pcm, 1.0 / 60.0, None);
+        }
+        analyzer.analyze(pcm, 1.0 / 60.0, None).to_vec()
+    }
+
+    #[test]
+    fn tone_peaks_in_its_bin() {
+        let bin_hz = RATE / FFT_SIZE as f32;
+        let spectrum = settled(&mut Analyzer::default(), &tone(40.0 * bin_hz, 0.01));
+        let peak = (0..spectrum.len()).max_by_key(|&i| spectrum[i]).unwrap();
+        assert_eq!(peak, 40);
+        // 0.01 * 0.42 / 2 ≈ -54 dB
+        assert!((160..180).contains(&spectrum[40]), "peak level {}", spectrum[40]);
+        assert!(spectrum[400] < 10, "far bins stay quiet: {}", spectrum[400]);
+    }
+
+    #[test]
+    fn silence_reads_zero() {
+        assert!(settled(&mut Analyzer::default(), &[]).iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn smoothing_is_frame_rate_independent() {
+        let pcm = tone(1000.0, 0.5);
+        let (mut slow, mut fast) = (Analyzer::default(), Analyzer::default());
+        let slow = slow.analyze(&pcm, 1.0 / 30.0, None).to_vec();
+        fast.analyze(&pcm, 1.0 / 60.0, None);
+        let fast = fast.analyze(&pcm, 1.0 / 60.0, None).to_vec();
+        let diff = slow.iter().zip(&fast).map(|(&a, &b)| (a as i32 - b as i32).abs()).max().unwrap();
+        assert!(diff <= 1, "max byte difference {diff}");
+    }
+
+    #[test]
+    fn auto_gain_evens_out_quiet_and_loud_tracks() {
+        let agc = AutoGain { max_db: 30.0, release_secs: 2.0 };
+        let peak = |amplitude: f32, agc: Option<&AutoGain>| {
+            let pcm = tone(1000.0, amplitude);
+            let mut analyzer = Analyzer::default();
+            for _ in 0..120 {
+                analyzer.analyze(&pcm, 1.0 / 60.0, agc);
+            }
+            *analyzer.analyze(&pcm, 1.0 / 60.0, agc).iter().max().unwrap()
+        };
+
+        assert!(peak(0.005, None) < 150, "quiet track barely moves without AGC");
+        assert_eq!(peak(0.9, None), 255, "hot master pins without AGC");
+        for amplitude in [0.005, 0.9] {
+            let level = peak(amplitude, Some(&agc));
+            assert!((235..=250).contains(&level), "amplitude {amplitude}: {level}");
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Mapping from spectrum bins to visualizer bands.
+//!
+//! Bands are spaced evenly on a perceptual scale between `band_min_hz` and
+//! `band_max_hz`, then converted to fractional bin ranges using the sample
+//! rate. Any bin count works, so preview (AnalyserNode) and export agree as
+//! long as both pass the full spectrum.
+
+use serde::{Deserialize, Serialize};
+
+/// Frequency axis bands are spaced evenly on.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum BandScale {
+    Linear,
+    /// Equal width per octave.
+    #[default]
+    Log,
+    /// Mel: close to linear below ~700 Hz, logarithmic above.
+    Mel,
+    /// Bark critical bands (`6 asinh(f / 600)`).
+    Bark,
+}
+
+impl BandScale {
+    fn forward(self, hz: f32) -> f32 {
+        match self {
+            BandScale::Linear => hz,
+            BandScale::Log => hz.max(1.0).ln(),
+            BandScale::Mel => 2595.0 * (1.0 + hz / 700.0).log10(),
+            BandScale::Bark => 6.0 * (hz / 600.0).asinh(),
+        }
+    }
+
+    fn inverse(self, v: f32) -> f32 {
+        match self {
+            BandScale::Linear => v,
+            BandScale::Log => v.exp(),
+            BandScale::Mel => 700.0 * (10f32.powf(v / 2595.0) - 1.0),
+            BandScale::Bark => 600.0 * (v / 6.0).sinh(),
+        }
+    }
+}
+
+/// Inputs that determine the band layout.
+#[derive(Clone, Copy, PartialEq)]
+pub struct BandSpec {
+    pub scale: BandScale,
+    pub count: usize,
+    pub min_hz: f32,
+    pub max_hz: f32,
+    /// Spectrum length (`frequencyBinCount`); bin `k` is centered on
+    /// `k * nyquist / bins`.
+    pub bins: usize,
+    pub sample_rate: f32,
+}
+
+/// Per-band fractional bin ranges, rebuilt only when the spec changes.
+#[derive(Default)]
+pub struct BandMap {
+    spec: Option<BandSpec>,
+    ranges: Vec<(f32, f32)>,
+}
+
+impl BandMap {
+    pub fn ranges(&mut self, spec: BandSpec) -> &[(f32, f32)] {
+        if self.spec != Some(spec) {
+            self.ranges = build_ranges(&spec);
+            self.spec = Some(spec);
+        }
+        &self.ranges
+    }
+}
+
+fn build_ranges(spec: &BandSpec) -> Vec<(f32, f32)> {
+    let nyquist = spec.sample_rate / 2.0;
+    let max_hz = spec.max_hz.min(nyquist).max(2.0);
+    let min_hz = spec.min_hz.clamp(1.0, max_hz - 1.0);
+    let bin_hz = nyquist / spec.bins.max(1) as f32;
+
+    let lo = spec.scale.forward(min_hz);
+    let hi = spec.scale.forward(max_hz);
+    let edge = |i: usize| {
+        let v = lo + (hi - lo) * i as f32 / spec.count as f32;
+        spec.scale.inverse(v) / bin_hz
+    };
+
+    (0..spec.count).map(|i| (edge(i), edge(i + 1))).collect()
+}
+
+/// Level (0..1) of `freq_data` over a fractional bin range: the mean of the
+/// bins inside it, or interpolated at its center when narrower than a bin.
+pub fn band_level(freq_data: &[u8], (lo, hi): (f32, f32)) -> f32 {
+    let bin = |k: usize| freq_data.get(k).map_or(0.0, |&v| v as f32);
+
+    let level = if hi - lo <= 1.0 {
+        let center = (lo + hi) / 2.0;
+        let k = center.floor();
+        let f = center - k;
+        bin(k as usize) * (1.0 - f) + bin(k as usize + 1) * f
+    } else {
+        let (first, last) = (lo.ceil() as usize, hi.ceil() as usize);
+        (first..last).map(bin).sum::<f32>() / (last - first) as f32
+    };
+    level / 255.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(scale: BandScale) -> BandSpec {
+        BandSpec { scale, count: 32, min_hz: 30.0, max_hz: 16_000.0, bins: 1024, sample_rate: 48_000.0 }
+    }
+
+    #[test]
+    fn bands_span_the_requested_range() {
+        for scale in [BandScale::Linear, BandScale::Log, BandScale::Mel, BandScale::Bark] {
+            let ranges = BandMap::default().ranges(spec(scale)).to_vec();
+            let bin_hz = 24_000.0 / 1024.0;
+            assert!((ranges[0].0 * bin_hz - 30.0).abs() < 0.1, "{scale:?}");
+            assert!((ranges[31].1 * bin_hz - 16_000.0).abs() < 1.0, "{scale:?}");
+            assert!(ranges.windows(2).all(|w| w[0].1 <= w[1].0 + 1e-3), "{scale:?} contiguous");
+        }
+    }
+
+    #[test]
+    fn log_scale_gives_bass_more_bands_than_linear() {
+        let below_250 = |scale| {
+            let bin_250 = 250.0 / (24_000.0 / 1024.0);
+            BandMap::default().ranges(spec(scale)).iter().filter(|r| r.1 <= bin_250).count()
+        };
+        assert_eq!(below_250(BandScale::Linear), 0);
+        assert!(below_250(BandScale::Log) >= 6);
+    }
+
+    #[test]
+    fn narrow_bands_interpolate_between_bins() {
+        let data = [0, 255, 0, 0];
+        assert!((band_level(&data, (1.0, 1.0)) - 1.0).abs() < 1e-6);
+        assert!((band_level(&data, (1.25, 1.75)) - 0.5).abs() < 1e-6);
+        assert_eq!(band_level(&data, (10.0, 20.0)), 0.0);
+    }
+}
@@ -0,0 +1,140 @@
+//! Onset / beat detection.
+//!
+//! Spectral flux (summed positive change between consecutive spectra) over
+//! the low end of `freq_data`, compared against an adaptive threshold: a
+//! running mean plus a multiple of the running mean deviation. `freq_data`
+//! is the engine analyzer's spectrum, so preview and export detect the same
+//! beats from the same bins.
+//!
+//! When export supplies a beat grid (`tempo.rs`), hits come from the grid
+//! instead, so tempo-locked effects stay in phase for the whole track.
+
+use crate::smoothing_factor;
+
+/// Share of the spectrum treated as "low" (~0-375 Hz at 48 kHz).
+const LOW_FRACTION: f32 = 1.0 / 64.0;
+/// Time constant of the running flux statistics (seconds).
+const WINDOW_SECS: f32 = 1.0;
+/// Deviations above the running mean a hit must reach.
+const SENSITIVITY: f32 = 1.5;
+/// Flux floor, so noise in near-silence doesn't register.
+const MIN_FLUX: f32 = 0.02;
+/// Shortest gap between two hits (seconds); one kick is one beat.
+const MIN_INTERVAL: f32 = 0.1;
+/// Time constant of `strength` falling back to zero after a hit (seconds).
+const PULSE_DECAY_SECS: f32 = 0.15;
+
+/// Detector output for one frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Beat {
+    /// True only on the frame an onset is detected.
+    pub hit: bool,
+    /// 0..1. Jumps to the onset's strength on a hit, then decays; usable
+    /// directly as a pulse envelope for flashes and zooms.
+    pub strength: f32,
+}
+
+#[derive(Default)]
+pub struct BeatDetector {
+    prev: Vec<u8>,
+    mean: f32,
+    deviation: f32,
+    /// Seconds until another hit may register.
+    cooldown: f32,
+    beat: Beat,
+}
+
+impl BeatDetector {
+    pub fn beat(&self) -> Beat {
+        self.beat
+    }
+
+    /// Feed one frame, `dt` seconds after the previous one. `grid` is the
+    /// beat grid's verdict for this frame (`TempoMap::advance`); `None`
+    /// falls back to detection.
+    pub fn update(&mut self, freq_data: &[u8], dt: f32, grid: Option<f32>) -> Beat {
+        let low = ((freq_data.len() as f32 * LOW_FRACTION).ceil() as usize).min(freq_data.len());
+        let current = &freq_data[..low];
+
+        // No flux on the first frame or after the spectrum layout changes
+        let flux = if self.prev.len() == low && low > 0 {
+            let rise: u32 = current
+                .iter()
+                .zip(&self.prev)
+                .map(|(&c, &p)| c.saturating_sub(p) as u32)
+                .sum();
+            rise as f32 / (255 * low) as f32
+        } else {
+            0.0
+        };
+        self.prev.clear();
+        self.prev.extend_from_slice(current);
+
+        let threshold = self.mean + SENSITIVITY * self.deviation + MIN_FLUX;
+        self.cooldown = (self.cooldown - dt).max(0.0);
+        let detected = flux > threshold && self.cooldown <= 0.0;
+        let hit = grid.map_or(detected, |s| s > 0.0);
+
+        // Statistics update after the test, so a hit isn't judged against itself
+        let k = smoothing_factor(dt, WINDOW_SECS);
+        self.mean += (flux - self.mean) * k;
+        self.deviation += ((flux - self.mean).abs() - self.deviation) * k;
+
+        let mut strength = self.beat.strength * (1.0 - smoothing_factor(dt, PULSE_DECAY_SECS));
+        if hit {
+            self.cooldown = MIN_INTERVAL;
+            // Saturating: twice the threshold is 0.5, four times is 0.75
+            strength = strength.max(grid.unwrap_or(1.0 - threshold / flux));
+        }
+
+        self.beat = Beat { hit, strength };
+        self.beat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINS: usize = 1024;
+    const DT: f32 = 1.0 / 60.0;
+
+    fn spectrum(level: u8) -> Vec<u8> {
+        vec![level; BINS]
+    }
+
+    #[test]
+    fn kicks_on_quiet_floor_are_detected() {
+        let mut detector = BeatDetector::default();
+        let mut hits = 0;
+        // Two seconds of 120 BPM: a loud frame every 30 frames
+        for frame in 0..120 {
+            let level = if frame % 30 == 15 { 220 } else { 40 };
+            if detector.update(&spectrum(level), DT, None).hit {
+                hits += 1;
+            }
+        }
+        assert_eq!(hits, 4);
+    }
+
+    #[test]
+    fn steady_spectrum_never_triggers() {
+        let mut detector = BeatDetector::default();
+        for _ in 0..120 {
+            assert!(!detector.update(&spectrum(180), DT, None).hit);
+        }
+        assert_eq!(detector.beat().strength, 0.0);
+    }
+
+    #[test]
+    fn strength_decays_after_a_hit() {
+        let mut detector = BeatDetector::default();
+        detector.update(&spectrum(20), DT, None);
+        let hit = detector.update(&spectrum(240), DT, None);
+        assert!(hit.hit && hit.strength > 0.5);
+
+        let later = (0..10).fold(hit, |_, _| detector.update(&spectrum(240), DT, None));
+        assert!(!later.hit);
+        assert!(later.strength < hit.strength * 0.5);
+    }
+}
@@ -1,6 +1,32 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 
+mod analyzer;
+mod bands;
+mod beat;
+mod glow;
+pub mod palette;
+mod particles;
+pub mod raster;
+mod spectrogram;
+mod tempo;
+
+use analyzer::{Analyzer, AutoGain};
+use bands::{band_level, BandMap, BandSpec};
+use beat::BeatDetector;
+use glow::{Glow, GlowParams};
+use palette::{hex_to_u32, Fill, Ramp};
+use particles::{Drive, ParticleSystem};
+use raster::{Canvas, Faded};
+use spectrogram::{Panel, Spectrogram};
+use tempo::TempoMap;
+
+pub use analyzer::FFT_SIZE;
+pub use bands::BandScale;
+pub use beat::Beat;
+pub use palette::{ColorStop, Palette};
+pub use tempo::{estimate_tempo, Tempo, TempoSection};
+
 // --- Types ---
 
 #[wasm_bindgen]
@@ -9,6 +35,49 @@ pub enum VisualizerMode {
     Bars,
     Orbital,
     Wave,
+    /// Scrolling waterfall of recent spectra.
+    Spectrogram,
+    /// Seeded particle burst driven by band energy.
+    Particles,
+    /// Stereo image (mid up, side across) with a phase correlation meter.
+    Goniometer,
+}
+
+/// Where the bar row sits in the frame.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum BarAnchor {
+    BottomLeft,
+    BottomCenter,
+    #[default]
+    BottomRight,
+    /// Top edge, centered; bars hang downward.
+    Top,
+    /// Bottom edge, spanning the width between the paddings.
+    FullWidth,
+}
+
+/// How bars are ordered and grown within the row.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum BarLayout {
+    /// Low to high, left to right; bars grow from the baseline.
+    #[default]
+    Standard,
+    /// Bass in the middle, treble at both edges.
+    Mirrored,
+    /// Bars grow symmetrically above and below the row's center line.
+    Centered,
+    /// Left channel on the left half, right on the right; bass in the middle.
+    Stereo,
+}
+
+/// How the Wave mode traces its input.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum WaveLayout {
+    /// One trace of the mid (L+R) signal.
+    #[default]
+    Mono,
+    /// Left above the center line, right below it mirrored.
+    Stereo,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,16 +85,156 @@ pub struct VibeSettings {
     pub visualizer_mode: VisualizerMode,
     pub visualizer_color: String,
     pub visualizer_intensity: f32,
+    /// Gradient/palette for visualizer elements; `Solid` uses `visualizer_color`.
+    #[serde(default)]
+    pub visualizer_palette: Palette,
+    /// Number of frequency bands (bars, or spoke pairs in Orbital).
+    #[serde(default = "default_bar_count")]
+    pub bar_count: usize,
+    /// Gap between bars as a fraction of bar width.
+    #[serde(default = "default_bar_gap_ratio")]
+    pub bar_gap_ratio: f32,
+    #[serde(default)]
+    pub bar_anchor: BarAnchor,
+    #[serde(default)]
+    pub bar_layout: BarLayout,
+    /// Time constant (seconds) for bars rising toward a louder level.
+    #[serde(default = "default_attack_secs")]
+    pub attack_secs: f32,
+    /// Time constant (seconds) for bars falling back toward a quieter level.
+    #[serde(default = "default_decay_secs")]
+    pub decay_secs: f32,
+    /// Bloom amount added back over the visualizer; 0 disables the pass.
+    #[serde(default)]
+    pub glow_strength: f32,
+    /// Bloom blur radius in reference pixels (1080px short side).
+    #[serde(default = "default_glow_radius")]
+    pub glow_radius: f32,
+    /// Luminance (0..1) a pixel needs before it blooms.
+    #[serde(default = "default_glow_threshold")]
+    pub glow_threshold: f32,
+    /// Seconds of history shown by the Spectrogram mode.
+    #[serde(default = "default_spectrogram_seconds")]
+    pub spectrogram_seconds: f32,
+    /// Normalize loudness by following the rolling spectral peak.
+    #[serde(default)]
+    pub auto_gain: bool,
+    /// Largest boost or cut automatic gain may apply (dB).
+    #[serde(default = "default_auto_gain_max_db")]
+    pub auto_gain_max_db: f32,
+    /// Seconds for automatic gain to recover after a loud passage.
+    #[serde(default = "default_auto_gain_release_secs")]
+    pub auto_gain_release_secs: f32,
+    /// Frequency axis the bands are spaced on.
+    #[serde(default)]
+    pub band_scale: BandScale,
+    /// Lowest frequency of the first band (Hz).
+    #[serde(default = "default_band_min_hz")]
+    pub band_min_hz: f32,
+    /// Highest frequency of the last band (Hz); capped at Nyquist.
+    #[serde(default = "default_band_max_hz")]
+    pub band_max_hz: f32,
+    /// Particles mode RNG seed. Same seed + same audio = same frames.
+    #[serde(default)]
+    pub particle_seed: u32,
+    /// Multiplier on particle emission rate and cap.
+    #[serde(default = "default_particle_density")]
+    pub particle_density: f32,
+    /// Wave mode: one mid trace or separate left/right traces.
+    #[serde(default)]
+    pub wave_layout: WaveLayout,
+}
+
+fn default_bar_count() -> usize {
+    DEFAULT_BAR_COUNT
+}
+
+fn default_bar_gap_ratio() -> f32 {
+    0.5
+}
+
+// Equivalent to the original per-frame factors (0.6 / 0.12) at 60fps
+fn default_attack_secs() -> f32 {
+    0.018
+}
+
+fn default_decay_secs() -> f32 {
+    0.13
+}
+
+fn default_glow_radius() -> f32 {
+    24.0
+}
+
+fn default_glow_threshold() -> f32 {
+    0.2
+}
+
+fn default_spectrogram_seconds() -> f32 {
+    4.0
+}
+
+fn default_particle_density() -> f32 {
+    1.0
+}
+
+fn default_auto_gain_max_db() -> f32 {
+    24.0
+}
+
+fn default_auto_gain_release_secs() -> f32 {
+    4.0
+}
+
+fn default_band_min_hz() -> f32 {
+    30.0
+}
+
+fn default_band_max_hz() -> f32 {
+    16_000.0
 }
 
 // --- Micro-Rasterizer (Pure Rust) ---
 
+/// Short-side length that layout constants are authored against.
+const REFERENCE_DIM: f32 = 1080.0;
+
+const DEFAULT_BAR_COUNT: usize = 12;
+const MAX_BAR_COUNT: usize = 128;
+
+/// Assumed until `set_sample_rate` is called.
+const DEFAULT_SAMPLE_RATE: f32 = 44_100.0;
+
+/// Step assumed for the first frame and after the clock jumps backwards.
+const NOMINAL_DT: f32 = 1.0 / 60.0;
+/// Longest step integrated at once; stalls (tab hidden, breakpoints) don't snap bars.
+const MAX_DT: f32 = 0.1;
+
 #[wasm_bindgen]
 pub struct VibeEngine {
     width: i32,
     height: i32,
     pixels: Vec<u32>, // ARGB buffer
     physics_state: Vec<f32>,
+    /// Per-channel band levels (left, right), same layout as `physics_state`.
+    channel_state: [Vec<f32>; 2],
+    /// Analyzes the mid signal, which drives everything mono.
+    analyzer: Analyzer,
+    channel_analyzers: [Analyzer; 2],
+    /// Scratch for mixing the mid signal.
+    mid: Vec<f32>,
+    /// Smoothed left/right correlation (-1..1) for the Goniometer meter.
+    correlation: f32,
+    bands: BandMap,
+    /// Sample rate of the analyzed PCM, for bin-to-Hz mapping.
+    sample_rate: f32,
+    /// Timestamp of the previous render, for deriving dt.
+    last_time: Option<f64>,
+    glow: Glow,
+    spectrogram: Spectrogram,
+    particles: ParticleSystem,
+    beat: BeatDetector,
+    tempo: TempoMap,
 }
 
 #[wasm_bindgen]
@@ -38,7 +247,7 @@ impl VibeEngine {
 
         let pixels = vec![0xFF000000; size]; 
 
-        let physics_state = vec![0.0; 64];
+        let physics_state = vec![0.0; DEFAULT_BAR_COUNT];
 
         VibeEngine {
 
@@ -50,6 +259,32 @@ impl VibeEngine {
 
             physics_state,
 
+            channel_state: [vec![0.0; DEFAULT_BAR_COUNT], vec![0.0; DEFAULT_BAR_COUNT]],
+
+            analyzer: Analyzer::default(),
+
+            channel_analyzers: [Analyzer::default(), Analyzer::default()],
+
+            mid: Vec::with_capacity(FFT_SIZE),
+
+            correlation: 0.0,
+
+            bands: BandMap::default(),
+
+            sample_rate: DEFAULT_SAMPLE_RATE,
+
+            last_time: None,
+
+            glow: Glow::default(),
+
+            spectrogram: Spectrogram::default(),
+
+            particles: ParticleSystem::default(),
+
+            beat: BeatDetector::default(),
+
+            tempo: TempoMap::default(),
+
         }
 
     }
@@ -68,6 +303,15 @@ impl VibeEngine {
 
 
 
+    /// Sample rate of the PCM passed to `render`; spectrum bins span 0..rate/2.
+    pub fn set_sample_rate(&mut self, rate: f32) {
+        if rate > 0.0 {
+            self.sample_rate = rate;
+        }
+    }
+
+
+
     pub fn get_pixel_ptr(&self) -> *const u32 {
 
         self.pixels.as_ptr()
@@ -76,16 +320,41 @@ impl VibeEngine {
 
 
 
-    pub fn render(&mut self, settings_val: JsValue, freq_data: &[u8], _time: f64) -> Result<(), JsValue> {
+    pub fn render(&mut self, settings_val: JsValue, pcm: &[f32], time: f64) -> Result<(), JsValue> {
 
         let settings: VibeSettings = serde_wasm_bindgen::from_value(settings_val)?;
 
-        self.render_native(&settings, freq_data);
+        self.render_native(&settings, pcm, time);
 
         Ok(())
 
     }
 
+    /// `render` with separate left and right channels.
+    pub fn render_stereo(&mut self, settings_val: JsValue, left: &[f32], right: &[f32], time: f64) -> Result<(), JsValue> {
+        let settings: VibeSettings = serde_wasm_bindgen::from_value(settings_val)?;
+        self.render_stereo_native(&settings, left, right, time);
+        Ok(())
+    }
+
+    /// True on the frame an onset was detected by the last render.
+    pub fn beat_hit(&self) -> bool {
+        self.beat.beat().hit
+    }
+
+    /// Beat pulse 0..1: the last onset's strength, decaying after the hit.
+    pub fn beat_strength(&self) -> f32 {
+        self.beat.beat().strength
+    }
+
+    /// Level 0..1 of the last render's (mid) spectrum between `min_hz` and
+    /// `max_hz`, e.g. 20-150 Hz for bass. Smoothed like the bars.
+    pub fn band_energy(&self, min_hz: f32, max_hz: f32) -> f32 {
+        let spectrum = self.analyzer.spectrum();
+        let bin_hz = self.sample_rate / 2.0 / spectrum.len().max(1) as f32;
+        band_level(spectrum, (min_hz.max(0.0) / bin_hz, max_hz.max(min_hz) / bin_hz))
+    }
+
 }
 
 
@@ -110,188 +379,606 @@ impl VibeEngine {
 
 
 
-    pub fn render_native(&mut self, settings: &VibeSettings, freq_data: &[u8]) {
-
-        // 1. Clear (Fast memset)
+    /// Beat grids on the render timeline (export, from `estimate_tempo`).
+    /// Inside a section, beat hits follow the grid instead of detection.
+    pub fn set_tempo_map(&mut self, sections: Vec<TempoSection>) {
+        self.tempo.set(sections);
+    }
 
-        self.pixels.fill(0x00000000); 
+    /// Beats elapsed at the last render (fractional part is the phase within
+    /// the beat), or `None` outside any tempo section.
+    pub fn beat_phase(&self) -> Option<f64> {
+        self.tempo.phase()
+    }
 
+    /// `pcm` is the most recent mono audio in -1.0..1.0, newest sample last
+    /// (`FFT_SIZE` samples; shorter is padded with silence, may be empty).
+    /// The spectrum is computed here, so preview and export see identical data.
+    /// `time` is in seconds on any monotonic clock; physics integrate over
+    /// the elapsed time between calls, so motion is frame-rate independent.
+    pub fn render_native(&mut self, settings: &VibeSettings, pcm: &[f32], time: f64) {
+        self.render_frame(settings, pcm, pcm, false, time);
+    }
 
+    /// `render_native` with separate, equally long left and right channels.
+    /// Mono modes see the mid signal (L+R)/2; stereo layouts read each side.
+    pub fn render_stereo_native(&mut self, settings: &VibeSettings, left: &[f32], right: &[f32], time: f64) {
+        self.render_frame(settings, left, right, true, time);
+    }
 
-        // 2. Physics
+    /// One frame. Each channel gets its own spectrum only when `split` (the
+    /// sides can differ) and the active layout draws per-channel levels;
+    /// otherwise both sides show the mid levels.
+    fn render_frame(&mut self, settings: &VibeSettings, left: &[f32], right: &[f32], split: bool, time: f64) {
 
-        self.update_physics(freq_data);
+        // 1. Clear (Fast memset)
 
+        self.pixels.fill(0x00000000); 
 
 
-        // 3. Draw
 
-        match settings.visualizer_mode {
+        // 2. Analysis + Physics
 
-            VisualizerMode::Bars => self.draw_bars(settings),
+        let dt = self.advance_clock(time);
 
-            _ => (), // TODO
+        // Taken out of `self` while the draw routines borrow it mutably
+        let mut mid = std::mem::take(&mut self.mid);
+        mid.clear();
+        mid.extend(left.iter().zip(right).map(|(l, r)| (l + r) * 0.5));
 
+        let auto_gain = settings.auto_gain.then_some(AutoGain {
+            max_db: settings.auto_gain_max_db,
+            release_secs: settings.auto_gain_release_secs,
+        });
+        self.analyzer.analyze(&mid, dt, auto_gain.as_ref());
+        let per_channel = split && uses_channel_levels(settings);
+        if per_channel {
+            // Channels follow the mid's gain so the two sides stay comparable
+            let gain_db = self.analyzer.gain_db();
+            self.channel_analyzers[0].analyze_at_gain(left, dt, gain_db);
+            self.channel_analyzers[1].analyze_at_gain(right, dt, gain_db);
         }
 
-    }
+        self.update_physics(settings, dt, per_channel);
 
+        let grid = self.tempo.advance(time, dt);
+        self.beat.update(self.analyzer.spectrum(), dt, grid);
 
 
-    fn update_physics(&mut self, freq_data: &[u8]) {
 
-        let attack = 0.6;
-
-        let decay = 0.12;
+        // 3. Draw
 
-        let bar_count = 32;
+        match settings.visualizer_mode {
 
+            VisualizerMode::Bars => self.draw_bars(settings),
 
+            VisualizerMode::Orbital => self.draw_orbital(settings),
 
-        for i in 0..bar_count {
+            VisualizerMode::Wave => match settings.wave_layout {
+                WaveLayout::Mono => self.draw_wave(settings, &mid),
+                WaveLayout::Stereo => self.draw_stereo_wave(settings, left, right),
+            },
 
-            let freq_index = (1.18f32.powf((i + 5) as f32)).floor() as usize;
+            VisualizerMode::Spectrogram => self.draw_spectrogram(settings, dt),
 
-            let mut target = 0.0;
+            VisualizerMode::Particles => self.draw_particles(settings, dt),
 
+            VisualizerMode::Goniometer => self.draw_goniometer(settings, left, right, dt),
 
+        }
 
-            if freq_index < freq_data.len() {
+        // 4. Post
+        self.apply_glow(settings);
 
-                let v1 = freq_data[freq_index] as f32;
+        self.mid = mid;
+    }
 
-                let v2 = if freq_index + 1 < freq_data.len() { freq_data[freq_index + 1] as f32 } else { 0.0 };
 
-                target = ((v1 + v2) / 2.0) / 255.0;
 
+    /// Smooth one level per band, for the mid signal and (`per_channel`)
+    /// each channel, else the channels copy the mid. Bands are spaced on
+    /// `band_scale` between `band_min_hz` and `band_max_hz` and read the full
+    /// spectrum, so changing the count changes resolution, not frequency range.
+    fn update_physics(&mut self, settings: &VibeSettings, dt: f32, per_channel: bool) {
+        let attack = smoothing_factor(dt, settings.attack_secs);
+        let decay = smoothing_factor(dt, settings.decay_secs);
+        let bar_count = settings.bar_count.clamp(1, MAX_BAR_COUNT);
+
+        let ranges = self.bands.ranges(BandSpec {
+            scale: settings.band_scale,
+            count: bar_count,
+            min_hz: settings.band_min_hz,
+            max_hz: settings.band_max_hz,
+            bins: self.analyzer.spectrum().len(),
+            sample_rate: self.sample_rate,
+        });
+
+        let smooth = |state: &mut Vec<f32>, spectrum: &[u8]| {
+            state.resize(bar_count, 0.0);
+            for (level, &range) in state.iter_mut().zip(ranges) {
+                let target = band_level(spectrum, range) * 1.3;
+                let alpha = if target > *level { attack } else { decay };
+                *level += (target - *level) * alpha;
+            }
+        };
+
+        smooth(&mut self.physics_state, self.analyzer.spectrum());
+        for (state, analyzer) in self.channel_state.iter_mut().zip(&self.channel_analyzers) {
+            if per_channel {
+                smooth(state, analyzer.spectrum());
+            } else {
+                state.clone_from(&self.physics_state);
             }
-
-            
-
-            target *= 1.3;
-
-
-
-            let current = self.physics_state[i];
-
-            let alpha = if target > current { attack } else { decay };
-
-            self.physics_state[i] = current + (target - current) * alpha;
-
         }
-
     }
 
-
-
+    /// Bar row placed by `bar_anchor` and `bar_layout`. Layout is authored in
+    /// reference pixels (1080px short side) and scaled, so 1080p, 4K and 9:16
+    /// frame identically.
     fn draw_bars(&mut self, settings: &VibeSettings) {
-
-        let padding = 80;
-
-        let viz_width = 600;
-
-        let origin_x = self.width - padding - viz_width;
-
-        let origin_y = self.height - padding;
-
-        
-
-        let visible_bars: i32 = 12; 
-
-        let bar_w: i32 = 24; 
-
-        let gap: i32 = 12; 
-
-        let max_h = 200.0 * settings.visualizer_intensity;
-
-
-
-        let color = self.hex_to_u32(&settings.visualizer_color);
-
-
-
-        for i in 0..visible_bars {
-
-            let idx = (i * 2) as usize;
-
-            let val = self.physics_state[idx];
-
-            let h = (val.powf(1.4) * max_h).max(4.0) as i32;
-
-
-
-            let x = (origin_x + viz_width) - ((visible_bars - i) * (bar_w + gap));
-
-            let y = origin_y - h;
-
-
-
-            self.fill_rect(x, y, bar_w, h, color);
-
+        let scale = self.layout_scale();
+        let width = self.width as f32;
+        let height = self.height as f32;
+
+        let padding = 80.0 * scale;
+        let max_h = 200.0 * scale * settings.visualizer_intensity;
+        let min_h = (4.0 * scale).max(1.0);
+
+        let count = self.physics_state.len();
+        let gap_ratio = settings.bar_gap_ratio.max(0.0);
+        let row_w = match settings.bar_anchor {
+            BarAnchor::FullWidth => width - padding * 2.0,
+            _ => 420.0 * scale,
+        };
+        let bar_w = row_w / (count as f32 + (count - 1) as f32 * gap_ratio);
+        let pitch = bar_w * (1.0 + gap_ratio);
+
+        let row_left = match settings.bar_anchor {
+            BarAnchor::BottomLeft | BarAnchor::FullWidth => padding,
+            BarAnchor::BottomCenter | BarAnchor::Top => (width - row_w) / 2.0,
+            BarAnchor::BottomRight => width - padding - row_w,
+        };
+        let hanging = settings.bar_anchor == BarAnchor::Top;
+        let baseline = if hanging { padding } else { height - padding };
+        let center_line = if hanging { baseline + max_h / 2.0 } else { baseline - max_h / 2.0 };
+
+        let ramp = settings.visualizer_palette.ramp(hex_to_u32(&settings.visualizer_color));
+        let extent = Extent {
+            left: row_left,
+            right: row_left + row_w,
+            top: if hanging { baseline } else { baseline - max_h },
+            bottom: if hanging { baseline + max_h } else { baseline },
+            center: (row_left + row_w / 2.0, baseline),
+            inner_r: 0.0,
+            outer_r: (row_w / 2.0).max(max_h),
+        };
+        let mut canvas = Canvas::new(&mut self.pixels, self.width, self.height);
+
+        for i in 0..count {
+            let val = match settings.bar_layout {
+                BarLayout::Mirrored => self.physics_state[mirrored_band(i, count)],
+                BarLayout::Stereo => {
+                    let side = if 2 * i < count { 0 } else { 1 };
+                    self.channel_state[side][mirrored_band(i, count)]
+                }
+                _ => self.physics_state[i],
+            };
+            let h = (val.powf(1.4) * max_h).max(min_h);
+
+            let top = match (settings.bar_layout, hanging) {
+                (BarLayout::Centered, _) => center_line - h / 2.0,
+                (_, true) => baseline,
+                (_, false) => baseline - h,
+            };
+
+            // Round both edges so bar widths and gaps stay uniform at any scale
+            let left = row_left + i as f32 * pitch;
+            let x = left.round() as i32;
+            let y = top.round() as i32;
+            let w = (left + bar_w).round() as i32 - x;
+            let h = (top + h).round() as i32 - y;
+
+            canvas.fill_rect(x, y, w, h, extent.fill(&settings.visualizer_palette, &ramp, val));
         }
-
     }
 
+    /// Waterfall panel across the lower part of the frame. The history only
+    /// advances while this mode is active.
+    fn draw_spectrogram(&mut self, settings: &VibeSettings, dt: f32) {
+        self.spectrogram.advance(self.analyzer.spectrum(), dt, settings.spectrogram_seconds);
+
+        let scale = self.layout_scale();
+        let padding = 80.0 * scale;
+        let panel_h = (self.height as f32 * 0.45).round();
+        let panel = Panel {
+            left: padding.round() as i32,
+            top: (self.height as f32 - padding - panel_h).round() as i32,
+            width: (self.width as f32 - padding * 2.0).round() as i32,
+            height: panel_h as i32,
+            gain: 1.3 * settings.visualizer_intensity,
+        };
+
+        let base = hex_to_u32(&settings.visualizer_color);
+        let ramp = match settings.visualizer_palette {
+            Palette::Solid => Ramp::heat(base),
+            ref palette => palette.ramp(base),
+        };
+        let mut canvas = Canvas::new(&mut self.pixels, self.width, self.height);
+        self.spectrogram.draw(&mut canvas, &panel, &ramp);
+    }
 
-
-    // --- Rasterizer Helpers ---
-
-
-
-    fn hex_to_u32(&self, hex: &str) -> u32 {
-
-        // Expects #RRGGBB
-
-        if hex.len() == 7 {
-
-            let r = u8::from_str_radix(&hex[1..3], 16).unwrap_or(255);
-
-            let g = u8::from_str_radix(&hex[3..5], 16).unwrap_or(255);
-
-            let b = u8::from_str_radix(&hex[5..7], 16).unwrap_or(255);
-
-            // ABGR Little Endian
-
-            return (255 << 24) | ((b as u32) << 16) | ((g as u32) << 8) | (r as u32);
-
+    /// Lissajous of the latest samples: mid (L+R) up, side (R-L) across, so
+    /// mono is a vertical line and wide mixes spread sideways. The meter
+    /// below shows smoothed L/R correlation: +1 mono, 0 wide, -1 out of phase.
+    fn draw_goniometer(&mut self, settings: &VibeSettings, left: &[f32], right: &[f32], dt: f32) {
+        let (mut lr, mut ll, mut rr) = (0.0f32, 0.0f32, 0.0f32);
+        for (&l, &r) in left.iter().zip(right) {
+            lr += l * r;
+            ll += l * l;
+            rr += r * r;
+        }
+        // Silence has no phase; hold the meter where it was
+        if ll * rr > 1e-12 {
+            let target = lr / (ll * rr).sqrt();
+            self.correlation += (target - self.correlation) * smoothing_factor(dt, 0.3);
         }
 
-        0xFF03B7FF // Default Plasma (ABGR)
-
+        let scale = self.layout_scale();
+        let radius = 320.0 * scale;
+        let cx = self.width as f32 / 2.0;
+        let cy = self.height as f32 / 2.0 - 50.0 * scale;
+        let meter_y = cy + radius + 70.0 * scale;
+        let gain = radius * settings.visualizer_intensity * 1.5 / std::f32::consts::SQRT_2;
+
+        let ramp = settings.visualizer_palette.ramp(hex_to_u32(&settings.visualizer_color));
+        let extent = Extent {
+            left: cx - radius,
+            right: cx + radius,
+            top: cy - radius,
+            bottom: meter_y,
+            center: (cx, cy),
+            inner_r: 0.0,
+            outer_r: radius,
+        };
+        let level = 1.0 - self.correlation.abs() * 0.5;
+        let paint = extent.fill(&settings.visualizer_palette, &ramp, level);
+        let guide = Faded(paint, 0.18);
+        let thin = (1.5 * scale).max(1.0);
+        let mut canvas = Canvas::new(&mut self.pixels, self.width, self.height);
+
+        // Guides: outline, M axis, and the L / R diagonals
+        let d = radius * std::f32::consts::FRAC_1_SQRT_2;
+        canvas.stroke_circle(cx, cy, radius, thin, guide);
+        canvas.line(cx, cy - radius, cx, cy + radius, thin, guide);
+        canvas.line(cx - d, cy - d, cx + d, cy + d, thin, guide);
+        canvas.line(cx + d, cy - d, cx - d, cy + d, thin, guide);
+
+        let trace: Vec<(f32, f32)> = left
+            .iter()
+            .zip(right)
+            .map(|(&l, &r)| {
+                let (x, y) = ((r - l) * gain, (l + r) * gain);
+                // Keep clipping peaks on the outline
+                let k = (radius / x.hypot(y).max(radius)).min(1.0);
+                (cx + x * k, cy - y * k)
+            })
+            .collect();
+        canvas.polyline(&trace, (2.0 * scale).max(1.0), Faded(paint, 0.75));
+
+        // Correlation meter: -1 on the left, +1 on the right
+        canvas.line(cx - radius, meter_y, cx + radius, meter_y, (4.0 * scale).max(1.0), guide);
+        canvas.line(cx, meter_y - 12.0 * scale, cx, meter_y + 12.0 * scale, thin, guide);
+        canvas.fill_circle(cx + self.correlation.clamp(-1.0, 1.0) * radius, meter_y, 10.0 * scale, paint);
     }
 
+    /// Particles radiating from the frame center. Bass sets the emission rate;
+    /// overall energy sets launch speed and size; beats fire extra bursts. Simulation only advances
+    /// while this mode is active.
+    fn draw_particles(&mut self, settings: &VibeSettings, dt: f32) {
+        let bands = &self.physics_state;
+        let low = bands.len().div_ceil(4);
+        let drive = Drive {
+            bass: bands[..low].iter().sum::<f32>() / low as f32,
+            energy: bands.iter().sum::<f32>() / bands.len() as f32,
+            intensity: settings.visualizer_intensity,
+            density: settings.particle_density.max(0.0),
+            burst: if self.beat.beat().hit { self.beat.beat().strength } else { 0.0 },
+        };
+        self.particles.advance(dt, settings.particle_seed, &drive);
+
+        let scale = self.layout_scale();
+        let cx = self.width as f32 / 2.0;
+        let cy = self.height as f32 / 2.0;
+        let reach = self.width.max(self.height) as f32 / 2.0;
+
+        let ramp = settings.visualizer_palette.ramp(hex_to_u32(&settings.visualizer_color));
+        let extent = Extent {
+            left: 0.0,
+            right: self.width as f32,
+            top: 0.0,
+            bottom: self.height as f32,
+            center: (cx, cy),
+            inner_r: 0.0,
+            outer_r: reach,
+        };
+        let mut canvas = Canvas::new(&mut self.pixels, self.width, self.height);
+
+        for p in self.particles.particles() {
+            let paint = extent.fill(&settings.visualizer_palette, &ramp, p.energy);
+            canvas.fill_circle(cx + p.x * scale, cy + p.y * scale, p.size * scale, Faded(paint, p.fade()));
+        }
+    }
 
+    /// Bloom pass over everything drawn this frame. Radius and downsample
+    /// factor follow `layout_scale()`, so glow looks the same at any resolution.
+    fn apply_glow(&mut self, settings: &VibeSettings) {
+        if settings.glow_strength <= 0.0 {
+            return;
+        }
+        let scale = self.layout_scale();
+        let params = GlowParams {
+            threshold: settings.glow_threshold,
+            radius: settings.glow_radius.max(0.0) * scale,
+            strength: settings.glow_strength,
+            factor: (4.0 * scale).round().clamp(1.0, 8.0) as usize,
+        };
+        self.glow.apply(&mut self.pixels, self.width, self.height, &params);
+    }
 
-    fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: u32) {
+    /// Seconds since the previous render, clamped to `MAX_DT`.
+    fn advance_clock(&mut self, time: f64) -> f32 {
+        let dt = match self.last_time {
+            Some(last) if time >= last => ((time - last) as f32).min(MAX_DT),
+            _ => NOMINAL_DT,
+        };
+        self.last_time = Some(time);
+        dt
+    }
 
-        // Clipping
+    /// Pixels per reference pixel, where the reference frame has a 1080px short side.
+    fn layout_scale(&self) -> f32 {
+        self.width.min(self.height) as f32 / REFERENCE_DIM
+    }
 
-        let x0 = x.max(0);
+    /// Radial spokes around a bass-driven ring, centered in the frame.
+    /// Bands are mirrored left/right so the shape stays symmetric.
+    fn draw_orbital(&mut self, settings: &VibeSettings) {
+        let bands = self.physics_state.len();
+        let spokes = bands * 2;
+
+        let cx = self.width as f32 / 2.0;
+        let cy = self.height as f32 / 2.0;
+        let min_dim = self.width.min(self.height) as f32;
+
+        let bass = (self.physics_state[0] + self.physics_state[1.min(bands - 1)]) / 2.0;
+        let base_r = min_dim * 0.16 * (1.0 + bass * 0.08 * settings.visualizer_intensity);
+        let max_len = min_dim * 0.18 * settings.visualizer_intensity;
+        let thickness = (4.0 * self.layout_scale()).max(2.0);
+
+        let ramp = settings.visualizer_palette.ramp(hex_to_u32(&settings.visualizer_color));
+        let outer_r = base_r + max_len;
+        let extent = Extent {
+            left: cx - outer_r,
+            right: cx + outer_r,
+            top: cy - outer_r,
+            bottom: cy + outer_r,
+            center: (cx, cy),
+            inner_r: base_r,
+            outer_r,
+        };
+        let mut canvas = Canvas::new(&mut self.pixels, self.width, self.height);
+
+        let step = std::f32::consts::TAU / spokes as f32;
+        for s in 0..spokes {
+            let band = if s < bands { s } else { spokes - 1 - s };
+            let val = self.physics_state[band];
+            let len = (val.powf(1.4) * max_len).max(thickness);
+
+            // Start at 12 o'clock; half-step offset keeps the mirror axis vertical
+            let angle = -std::f32::consts::FRAC_PI_2 + (s as f32 + 0.5) * step;
+            let (sin, cos) = angle.sin_cos();
+
+            canvas.line(
+                cx + cos * base_r,
+                cy + sin * base_r,
+                cx + cos * (base_r + len),
+                cy + sin * (base_r + len),
+                thickness,
+                extent.fill(&settings.visualizer_palette, &ramp, val),
+            );
+        }
 
-        let y0 = y.max(0);
+        let ring = extent.fill(&settings.visualizer_palette, &ramp, bass);
+        canvas.stroke_circle(cx, cy, base_r - thickness * 1.5, thickness, ring);
+    }
 
-        let x1 = (x + w).min(self.width);
+    /// Oscilloscope line of the mid signal across the frame.
+    fn draw_wave(&mut self, settings: &VibeSettings, pcm: &[f32]) {
+        let amplitude = 270.0 * self.layout_scale() * settings.visualizer_intensity;
+        self.draw_trace(settings, pcm, self.height as f32 / 2.0, amplitude);
+    }
 
-        let y1 = (y + h).min(self.height);
+    /// Left trace above the center line, right below it and flipped, so a
+    /// centered mix reads as a mirror image and stereo movement breaks it.
+    fn draw_stereo_wave(&mut self, settings: &VibeSettings, left: &[f32], right: &[f32]) {
+        let scale = self.layout_scale();
+        let center_y = self.height as f32 / 2.0;
+        let offset = 160.0 * scale;
+        let amplitude = 135.0 * scale * settings.visualizer_intensity;
+        self.draw_trace(settings, left, center_y - offset, amplitude);
+        self.draw_trace(settings, right, center_y + offset, -amplitude);
+    }
 
+    /// One waveform across the frame. Samples are box-filtered down to one
+    /// point every few pixels, then tapered at the edges so the line settles
+    /// onto `center_y` instead of clipping at the borders. Negative
+    /// `amplitude` flips the trace.
+    fn draw_trace(&mut self, settings: &VibeSettings, samples: &[f32], center_y: f32, amplitude: f32) {
+        let width = self.width as f32;
+        let scale = self.layout_scale();
+
+        let padding = width * 0.08;
+        let span = width - padding * 2.0;
+        let reach = amplitude.abs();
+        let thickness = (6.0 * scale).max(2.0);
+
+        let ramp = settings.visualizer_palette.ramp(hex_to_u32(&settings.visualizer_color));
+        let paint = match settings.visualizer_palette {
+            // Level of a waveform is its distance from the center line
+            Palette::Amplitude(_) => Fill::linear(&ramp, (0.0, center_y), (0.0, center_y - reach), true),
+            _ => Extent {
+                left: padding,
+                right: padding + span,
+                top: center_y - reach,
+                bottom: center_y + reach,
+                center: (width / 2.0, center_y),
+                inner_r: 0.0,
+                outer_r: span / 2.0,
+            }
+            .fill(&settings.visualizer_palette, &ramp, 0.0),
+        };
+
+        let point_count = ((span / (thickness * 1.5)) as usize).max(2);
+        let points = smooth_wave(samples, point_count);
+
+        let line: Vec<(f32, f32)> = points
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let t = i as f32 / (point_count - 1) as f32;
+                let taper = (t * std::f32::consts::PI).sin().powf(0.5);
+                (padding + t * span, center_y - v * taper * amplitude)
+            })
+            .collect();
+
+        Canvas::new(&mut self.pixels, self.width, self.height).polyline(&line, thickness, paint);
+    }
+}
 
+/// Region a visualizer occupies, used to orient positional gradients.
+struct Extent {
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+    /// Origin for radial gradients.
+    center: (f32, f32),
+    inner_r: f32,
+    outer_r: f32,
+}
 
-        if x0 >= x1 || y0 >= y1 { return; }
+impl Extent {
+    /// Paint for one element. `level` is the element's amplitude, used only
+    /// by `Palette::Amplitude`.
+    fn fill<'a>(&self, palette: &Palette, ramp: &'a Ramp, level: f32) -> Fill<'a> {
+        let mid_x = (self.left + self.right) / 2.0;
+        let mid_y = (self.top + self.bottom) / 2.0;
+        match palette {
+            Palette::Solid => Fill::Solid(ramp.sample(0.0)),
+            Palette::Vertical(_) => Fill::linear(ramp, (mid_x, self.bottom), (mid_x, self.top), false),
+            Palette::Horizontal(_) => Fill::linear(ramp, (self.left, mid_y), (self.right, mid_y), false),
+            Palette::Radial(_) => Fill::Radial {
+                ramp,
+                center: self.center,
+                r0: self.inner_r,
+                r1: self.outer_r,
+            },
+            Palette::Amplitude(_) => Fill::Solid(ramp.sample(level)),
+        }
+    }
+}
 
+/// Whether `settings` draw separate left/right band levels.
+fn uses_channel_levels(settings: &VibeSettings) -> bool {
+    matches!(settings.visualizer_mode, VisualizerMode::Bars) && settings.bar_layout == BarLayout::Stereo
+}
 
+/// Exponential smoothing factor for a step of `dt` seconds toward a target
+/// with time constant `tau`. Zero `tau` snaps immediately.
+fn smoothing_factor(dt: f32, tau: f32) -> f32 {
+    if tau <= 0.0 {
+        return 1.0;
+    }
+    1.0 - (-dt / tau).exp()
+}
 
-        for cy in y0..y1 {
+/// Band shown at position `i` of a mirrored row: band 0 at the center,
+/// stepping outward by two so both halves still span the full spectrum.
+fn mirrored_band(i: usize, count: usize) -> usize {
+    (2 * i + 1).abs_diff(count) & !1
+}
 
-            let start = (cy * self.width + x0) as usize;
+/// Resample `samples` to `count` points (box filter) and apply a 3-tap blur.
+/// Empty input yields a flat line.
+fn smooth_wave(samples: &[f32], count: usize) -> Vec<f32> {
+    let mut points = vec![0.0f32; count];
+    if samples.is_empty() {
+        return points;
+    }
 
-            let end = (cy * self.width + x1) as usize;
+    let chunk = samples.len() as f32 / count as f32;
+    for (i, p) in points.iter_mut().enumerate() {
+        let start = (i as f32 * chunk) as usize;
+        let end = (((i + 1) as f32 * chunk) as usize).clamp(start + 1, samples.len());
+        let slice = &samples[start.min(samples.len() - 1)..end];
+        *p = slice.iter().sum::<f32>() / slice.len() as f32;
+    }
 
-            self.pixels[start..end].fill(color);
+    let raw = points.clone();
+    for i in 1..count.saturating_sub(1) {
+        points[i] = (raw[i - 1] + raw[i] * 2.0 + raw[i + 1]) / 4.0;
+    }
+    points
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn band_energy_follows_the_spectrum() {
+        let settings: VibeSettings = serde_json::from_str(
+            r##"{ "visualizer_mode": "Bars", "visualizer_color": "#ffffff", "visualizer_intensity": 1.0 }"##,
+        )
+        .unwrap();
+        let rate = 48_000.0;
+        let kick: Vec<f32> = (0..FFT_SIZE)
+            .map(|i| (std::f32::consts::TAU * 60.0 * i as f32 / rate).sin() * 0.5)
+            .collect();
+
+        let mut engine = VibeEngine::new(64, 36);
+        engine.set_sample_rate(rate);
+        for frame in 0..30 {
+            engine.render_native(&settings, &kick, frame as f64 / 30.0);
         }
-
+        let bass = engine.band_energy(20.0, 150.0);
+        let treble = engine.band_energy(4_000.0, 16_000.0);
+        assert!(bass > 0.7, "bass {bass}");
+        assert!(treble < 0.1, "treble {treble}");
     }
 
+    #[test]
+    fn channels_are_analysed_only_for_stereo_layouts() {
+        let settings = |layout: &str| -> VibeSettings {
+            serde_json::from_value(serde_json::json!({
+                "visualizer_mode": "Bars", "visualizer_color": "#ffffff", "visualizer_intensity": 1.0,
+                "bar_layout": layout,
+            }))
+            .unwrap()
+        };
+        let tone: Vec<f32> = (0..FFT_SIZE).map(|i| (i as f32 * 0.3).sin() * 0.5).collect();
+        let analysed = |engine: &VibeEngine| engine.channel_analyzers.iter().any(|a| a.spectrum().iter().any(|&v| v > 0));
+
+        // Mono: both sides show the mid, no per-channel FFT
+        let mut engine = VibeEngine::new(64, 36);
+        engine.render_native(&settings("Stereo"), &tone, 0.1);
+        assert!(!analysed(&engine));
+        assert_eq!(engine.channel_state[0], engine.physics_state);
+
+        // Stereo input, but a layout without per-channel levels
+        engine.render_stereo_native(&settings("Standard"), &tone, &tone, 0.2);
+        assert!(!analysed(&engine));
+        engine.render_stereo_native(&settings("Stereo"), &tone, &tone, 0.3);
+        assert!(analysed(&engine));
+    }
 }
-
-
@@ -0,0 +1,202 @@
+//! Color palettes: solid, positional gradients and amplitude-mapped ramps.
+//!
+//! A `Palette` is the serialized setting. Each frame it is baked into a
+//! `Ramp` (256-entry lookup table), and each draw routine pairs the ramp with
+//! its own geometry as a `Fill` so gradients span the visualizer, not the frame.
+
+use serde::{Deserialize, Serialize};
+
+use crate::raster::Paint;
+
+const RAMP_SIZE: usize = 256;
+
+/// Fallback when a hex color can't be parsed (Plasma, ABGR).
+const FALLBACK_COLOR: u32 = 0xFF03B7FF;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColorStop {
+    /// Position along the gradient, 0.0..=1.0.
+    pub offset: f32,
+    /// `#RRGGBB` or `#RRGGBBAA`.
+    pub color: String,
+}
+
+/// How visualizer elements are colored.
+///
+/// Serialized adjacently tagged, e.g.
+/// `{ "kind": "Vertical", "stops": [{ "offset": 0, "color": "#06b6d4" }, ...] }`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(tag = "kind", content = "stops")]
+pub enum Palette {
+    /// Flat `visualizer_color`.
+    #[default]
+    Solid,
+    /// Bottom (0.0) to top (1.0) of the visualizer's extent.
+    Vertical(Vec<ColorStop>),
+    /// Left (0.0) to right (1.0) of the visualizer's extent.
+    Horizontal(Vec<ColorStop>),
+    /// Inner (0.0) to outer (1.0) edge, from the visualizer's center.
+    Radial(Vec<ColorStop>),
+    /// By level: silence (0.0) to peak (1.0).
+    Amplitude(Vec<ColorStop>),
+}
+
+impl Palette {
+    /// Bake the palette into a lookup table. `Solid` (or an empty stop list)
+    /// yields a flat ramp of `solid`.
+    pub fn ramp(&self, solid: u32) -> Ramp {
+        match self {
+            Palette::Solid => Ramp::flat(solid),
+            Palette::Vertical(stops)
+            | Palette::Horizontal(stops)
+            | Palette::Radial(stops)
+            | Palette::Amplitude(stops) => Ramp::from_stops(stops, solid),
+        }
+    }
+}
+
+/// Precomputed gradient colors, sampled by `t` in 0..=1.
+pub struct Ramp {
+    lut: Vec<u32>,
+}
+
+impl Ramp {
+    fn flat(color: u32) -> Self {
+        Self { lut: vec![color; RAMP_SIZE] }
+    }
+
+    /// Dark shade of `base` through `base` to white; the default colormap for
+    /// intensity displays (spectrogram) when the palette is `Solid`.
+    pub fn heat(base: u32) -> Self {
+        let [r, g, b, a] = unpack(base);
+        Self::from_parsed(vec![
+            (0.0, [r * 0.3, g * 0.3, b * 0.3, a]),
+            (0.65, [r, g, b, a]),
+            (1.0, [255.0, 255.0, 255.0, a]),
+        ])
+    }
+
+    fn from_stops(stops: &[ColorStop], fallback: u32) -> Self {
+        let parsed: Vec<(f32, [f32; 4])> = stops
+            .iter()
+            .map(|s| (s.offset.clamp(0.0, 1.0), unpack(hex_to_u32(&s.color))))
+            .collect();
+        if parsed.is_empty() {
+            return Self::flat(fallback);
+        }
+        Self::from_parsed(parsed)
+    }
+
+    /// Build the LUT from `(offset, [r, g, b, a])` stops (non-empty).
+    fn from_parsed(mut parsed: Vec<(f32, [f32; 4])>) -> Self {
+        parsed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let lut = (0..RAMP_SIZE)
+            .map(|i| {
+                let t = i as f32 / (RAMP_SIZE - 1) as f32;
+                let upper = parsed.iter().position(|(offset, _)| *offset >= t);
+                let rgba = match upper {
+                    None => parsed[parsed.len() - 1].1,
+                    Some(0) => parsed[0].1,
+                    Some(j) => {
+                        let (o0, c0) = parsed[j - 1];
+                        let (o1, c1) = parsed[j];
+                        let f = if o1 > o0 { (t - o0) / (o1 - o0) } else { 1.0 };
+                        std::array::from_fn(|k| c0[k] + (c1[k] - c0[k]) * f)
+                    }
+                };
+                pack(rgba)
+            })
+            .collect();
+
+        Self { lut }
+    }
+
+    #[inline]
+    pub fn sample(&self, t: f32) -> u32 {
+        let i = (t.clamp(0.0, 1.0) * (RAMP_SIZE - 1) as f32).round() as usize;
+        self.lut[i]
+    }
+}
+
+/// A ramp mapped onto drawing geometry.
+#[derive(Clone, Copy)]
+pub enum Fill<'a> {
+    Solid(u32),
+    /// `t` is the projection of `(x, y) - origin` onto `axis`, where `axis`
+    /// is pre-divided by the gradient length. `mirrored` uses `|t|`, for
+    /// shapes that grow both ways from a center line.
+    Linear {
+        ramp: &'a Ramp,
+        origin: (f32, f32),
+        axis: (f32, f32),
+        mirrored: bool,
+    },
+    /// `t` runs from `r0` (0.0) to `r1` (1.0) around `center`.
+    Radial {
+        ramp: &'a Ramp,
+        center: (f32, f32),
+        r0: f32,
+        r1: f32,
+    },
+}
+
+impl<'a> Fill<'a> {
+    /// Linear gradient from `from` (t = 0) to `to` (t = 1).
+    pub fn linear(ramp: &'a Ramp, from: (f32, f32), to: (f32, f32), mirrored: bool) -> Self {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let len_sq = (dx * dx + dy * dy).max(f32::EPSILON);
+        Fill::Linear {
+            ramp,
+            origin: from,
+            axis: (dx / len_sq, dy / len_sq),
+            mirrored,
+        }
+    }
+}
+
+impl Paint for Fill<'_> {
+    #[inline]
+    fn color_at(&self, x: f32, y: f32) -> u32 {
+        match *self {
+            Fill::Solid(color) => color,
+            Fill::Linear { ramp, origin, axis, mirrored } => {
+                let t = (x - origin.0) * axis.0 + (y - origin.1) * axis.1;
+                ramp.sample(if mirrored { t.abs() } else { t })
+            }
+            Fill::Radial { ramp, center, r0, r1 } => {
+                let d = (x - center.0).hypot(y - center.1);
+                ramp.sample((d - r0) / (r1 - r0).max(f32::EPSILON))
+            }
+        }
+    }
+
+    #[inline]
+    fn solid(&self) -> Option<u32> {
+        match *self {
+            Fill::Solid(color) => Some(color),
+            _ => None,
+        }
+    }
+}
+
+/// Parse `#RRGGBB` / `#RRGGBBAA` into packed RGBA (little-endian ABGR).
+pub fn hex_to_u32(hex: &str) -> u32 {
+    let h = match hex.strip_prefix('#') {
+        Some(h) if h.len() == 6 || h.len() == 8 => h,
+        _ => return FALLBACK_COLOR,
+    };
+    let byte = |i: usize| u8::from_str_radix(&h[i..i + 2], 16).unwrap_or(255) as u32;
+    let a = if h.len() == 8 { byte(6) } else { 255 };
+    (a << 24) | (byte(4) << 16) | (byte(2) << 8) | byte(0)
+}
+
+fn unpack(c: u32) -> [f32; 4] {
+    std::array::from_fn(|k| ((c >> (k * 8)) & 0xFF) as f32)
+}
+
+fn pack(c: [f32; 4]) -> u32 {
+    c.iter()
+        .enumerate()
+        .fold(0, |acc, (k, v)| acc | ((v.round() as u32).min(255) << (k * 8)))
+}
@@ -0,0 +1,210 @@
+//! Audio-reactive particle system.
+//!
+//! Simulation runs on a fixed step with a seeded RNG, so the same audio,
+//! settings and frame times always produce the same frames: a re-export is
+//! bit-identical, and 30fps/60fps renders agree wherever their timestamps meet.
+//! Positions are in reference pixels (1080px short side) around the emitter.
+
+/// Fixed simulation step (seconds).
+const SIM_STEP: f32 = 1.0 / 120.0;
+/// Hard cap on live particles at density 1.0.
+const BASE_CAPACITY: usize = 1500;
+/// Particles per second at full energy and density 1.0.
+const MAX_EMISSION: f32 = 600.0;
+/// Extra particles fired by a full-strength beat at density 1.0.
+const BURST_SIZE: f32 = 80.0;
+/// Velocity damping per second.
+const DRAG: f32 = 0.9;
+
+pub struct Particle {
+    pub x: f32,
+    pub y: f32,
+    vx: f32,
+    vy: f32,
+    age: f32,
+    life: f32,
+    /// Radius in reference pixels.
+    pub size: f32,
+    /// Band energy at birth (0..1), for amplitude palettes.
+    pub energy: f32,
+}
+
+impl Particle {
+    /// Remaining life, 1.0 at birth to 0.0 at death.
+    pub fn fade(&self) -> f32 {
+        (1.0 - self.age / self.life).clamp(0.0, 1.0)
+    }
+}
+
+/// Audio drive for one frame.
+pub struct Drive {
+    /// Low-band level (0..~1.3); controls emission rate.
+    pub bass: f32,
+    /// Mean level across bands; controls launch speed and size.
+    pub energy: f32,
+    pub intensity: f32,
+    pub density: f32,
+    /// Beat strength on the frame of a hit, otherwise 0.0.
+    pub burst: f32,
+}
+
+#[derive(Default)]
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    rng: Rng,
+    seed: Option<u32>,
+    /// Seconds not yet simulated.
+    pending: f32,
+    /// Fractional particles carried to the next step.
+    emit_carry: f32,
+}
+
+impl ParticleSystem {
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// Simulate `dt` seconds. A new `seed` restarts the system.
+    pub fn advance(&mut self, dt: f32, seed: u32, drive: &Drive) {
+        if self.seed != Some(seed) {
+            *self = Self {
+                rng: Rng::new(seed),
+                seed: Some(seed),
+                ..Self::default()
+            };
+        }
+
+        let capacity = (BASE_CAPACITY as f32 * drive.density.clamp(0.0, 4.0)) as usize;
+        let level = drive.bass.clamp(0.0, 1.3);
+        let rate = MAX_EMISSION * drive.density * level.powf(1.5);
+        // Released on the next step, so bursts land on the same step at any frame rate
+        self.emit_carry += BURST_SIZE * drive.density * drive.burst.clamp(0.0, 1.0);
+
+        // Tolerance absorbs float error so 1/30 and 2 x 1/60 take the same steps
+        self.pending += dt;
+        while self.pending >= SIM_STEP - 1e-5 {
+            self.pending -= SIM_STEP;
+            self.step(rate, capacity, drive);
+        }
+    }
+
+    fn step(&mut self, rate: f32, capacity: usize, drive: &Drive) {
+        self.emit_carry += rate * SIM_STEP;
+        while self.emit_carry >= 1.0 {
+            self.emit_carry -= 1.0;
+            if self.particles.len() < capacity {
+                let p = self.spawn(drive);
+                self.particles.push(p);
+            }
+        }
+
+        let damping = (-DRAG * SIM_STEP).exp();
+        for p in &mut self.particles {
+            p.vx *= damping;
+            p.vy *= damping;
+            p.x += p.vx * SIM_STEP;
+            p.y += p.vy * SIM_STEP;
+            p.age += SIM_STEP;
+        }
+        self.particles.retain(|p| p.age < p.life);
+    }
+
+    fn spawn(&mut self, drive: &Drive) -> Particle {
+        let angle = self.rng.next_f32() * std::f32::consts::TAU;
+        let energy = drive.energy.clamp(0.0, 1.0);
+        let speed = (120.0 + 520.0 * energy * self.rng.range(0.6, 1.2)) * drive.intensity.max(0.1);
+        let (sin, cos) = angle.sin_cos();
+        // Start on a small ring so the emitter reads as a source, not a dot
+        let start = self.rng.range(20.0, 60.0);
+
+        Particle {
+            x: cos * start,
+            y: sin * start,
+            vx: cos * speed,
+            vy: sin * speed,
+            age: 0.0,
+            life: self.rng.range(1.2, 2.8),
+            size: self.rng.range(2.0, 4.0) + 6.0 * energy,
+            energy,
+        }
+    }
+}
+
+/// xorshift32: tiny, fast and identical on every target.
+struct Rng(u32);
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Rng {
+    fn new(seed: u32) -> Self {
+        // Zero is a fixed point of xorshift; mix the seed so every value works
+        Self((seed.wrapping_mul(0x9E37_79B9) ^ 0x6D2B_79F5) | 1)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    /// Uniform in `0.0..1.0`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    fn range(&mut self, lo: f32, hi: f32) -> f32 {
+        lo + (hi - lo) * self.next_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drive() -> Drive {
+        Drive { bass: 0.9, energy: 0.6, intensity: 1.0, density: 1.0, burst: 0.0 }
+    }
+
+    fn snapshot(system: &ParticleSystem) -> Vec<(f32, f32, f32)> {
+        system.particles().iter().map(|p| (p.x, p.y, p.size)).collect()
+    }
+
+    #[test]
+    fn same_seed_same_particles() {
+        let mut a = ParticleSystem::default();
+        let mut b = ParticleSystem::default();
+        for _ in 0..90 {
+            a.advance(1.0 / 30.0, 7, &drive());
+            b.advance(1.0 / 30.0, 7, &drive());
+        }
+        assert!(!a.particles().is_empty());
+        assert_eq!(snapshot(&a), snapshot(&b));
+    }
+
+    #[test]
+    fn frame_rate_does_not_change_the_simulation() {
+        let mut slow = ParticleSystem::default();
+        let mut fast = ParticleSystem::default();
+        for _ in 0..30 {
+            slow.advance(1.0 / 30.0, 7, &drive());
+            fast.advance(1.0 / 60.0, 7, &drive());
+            fast.advance(1.0 / 60.0, 7, &drive());
+        }
+        assert_eq!(snapshot(&slow), snapshot(&fast));
+    }
+
+    #[test]
+    fn silence_emits_nothing() {
+        let mut system = ParticleSystem::default();
+        let quiet = Drive { bass: 0.0, ..drive() };
+        system.advance(1.0, 7, &quiet);
+        assert!(system.particles().is_empty());
+    }
+}
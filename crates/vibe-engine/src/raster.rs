@@ -0,0 +1,444 @@
+//! Anti-aliased drawing primitives over the engine's pixel buffer.
+//!
+//! Pixels are straight-alpha RGBA packed little-endian into `u32` (bytes
+//! R, G, B, A), matching `ImageData` and the native composer. Shapes compute
+//! per-pixel coverage (signed distance for round shapes, sub-scanlines for
+//! polygons) and blend with source-over.
+
+/// Sub-scanlines per pixel row for polygon coverage.
+const POLY_SUBSAMPLES: usize = 4;
+
+/// Per-pixel color source for shapes. A bare `u32` paints a flat color;
+/// gradients live in `palette::Fill`.
+pub trait Paint {
+    fn color_at(&self, x: f32, y: f32) -> u32;
+
+    /// The constant color, when the paint doesn't vary (enables fast paths).
+    fn solid(&self) -> Option<u32> {
+        None
+    }
+}
+
+impl Paint for u32 {
+    #[inline]
+    fn color_at(&self, _x: f32, _y: f32) -> u32 {
+        *self
+    }
+
+    #[inline]
+    fn solid(&self) -> Option<u32> {
+        Some(*self)
+    }
+}
+
+/// `paint` with its alpha scaled by a constant factor (fades, trails).
+#[derive(Clone, Copy)]
+pub struct Faded<P>(pub P, pub f32);
+
+impl<P: Paint> Paint for Faded<P> {
+    #[inline]
+    fn color_at(&self, x: f32, y: f32) -> u32 {
+        scale_alpha(self.0.color_at(x, y), self.1)
+    }
+
+    #[inline]
+    fn solid(&self) -> Option<u32> {
+        self.0.solid().map(|c| scale_alpha(c, self.1))
+    }
+}
+
+#[inline]
+fn scale_alpha(color: u32, factor: f32) -> u32 {
+    let a = ((color >> 24) as f32 * factor.clamp(0.0, 1.0) + 0.5) as u32;
+    (color & 0x00FF_FFFF) | (a << 24)
+}
+
+pub struct Canvas<'a> {
+    pixels: &'a mut [u32],
+    width: i32,
+    height: i32,
+}
+
+/// Clipped pixel bounds `[x0, x1) x [y0, y1)`.
+struct Bounds {
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+}
+
+impl<'a> Canvas<'a> {
+    pub fn new(pixels: &'a mut [u32], width: i32, height: i32) -> Self {
+        debug_assert_eq!(pixels.len(), (width.max(0) * height.max(0)) as usize);
+        Self { pixels, width, height }
+    }
+
+    /// Axis-aligned rectangle on whole pixels (no anti-aliasing needed).
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, paint: impl Paint) {
+        let x0 = x.max(0);
+        let y0 = y.max(0);
+        let x1 = (x + w).min(self.width);
+        let y1 = (y + h).min(self.height);
+
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        if let Some(color) = paint.solid().filter(|c| c >> 24 == 0xFF) {
+            for cy in y0..y1 {
+                let start = (cy * self.width + x0) as usize;
+                let end = (cy * self.width + x1) as usize;
+                self.pixels[start..end].fill(color);
+            }
+            return;
+        }
+
+        let b = Bounds { x0, y0, x1, y1 };
+        self.shade(&b, paint, |_, _| 1.0);
+    }
+
+    /// Line segment of the given thickness with round caps.
+    pub fn line(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, thickness: f32, paint: impl Paint) {
+        let half = thickness / 2.0;
+        let Some(b) = self.bounds(x0.min(x1) - half, y0.min(y1) - half, x0.max(x1) + half, y0.max(y1) + half) else {
+            return;
+        };
+        self.shade(&b, paint, |px, py| coverage(half - segment_distance(px, py, x0, y0, x1, y1)));
+    }
+
+    /// Connected line through `points` with round joins. Coverage is merged
+    /// per pixel before blending, so joints don't double up on alpha.
+    pub fn polyline(&mut self, points: &[(f32, f32)], thickness: f32, paint: impl Paint) {
+        if points.len() < 2 {
+            return;
+        }
+        let half = thickness / 2.0;
+        let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+        let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+        for &(x, y) in points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let Some(b) = self.bounds(min_x - half, min_y - half, max_x + half, max_y + half) else {
+            return;
+        };
+
+        let mask_w = (b.x1 - b.x0) as usize;
+        let mut mask = vec![0.0f32; mask_w * (b.y1 - b.y0) as usize];
+
+        for seg in points.windows(2) {
+            let ((ax, ay), (bx, by)) = (seg[0], seg[1]);
+            let Some(sb) = self.bounds(ax.min(bx) - half, ay.min(by) - half, ax.max(bx) + half, ay.max(by) + half) else {
+                continue;
+            };
+            for py in sb.y0..sb.y1 {
+                let row = (py - b.y0) as usize * mask_w;
+                for px in sb.x0..sb.x1 {
+                    let c = coverage(half - segment_distance(px as f32 + 0.5, py as f32 + 0.5, ax, ay, bx, by));
+                    let m = &mut mask[row + (px - b.x0) as usize];
+                    *m = m.max(c);
+                }
+            }
+        }
+
+        for py in b.y0..b.y1 {
+            let row = (py - b.y0) as usize * mask_w;
+            for px in b.x0..b.x1 {
+                let c = mask[row + (px - b.x0) as usize];
+                if c > 0.0 {
+                    self.blend(px, py, paint.color_at(px as f32 + 0.5, py as f32 + 0.5), c);
+                }
+            }
+        }
+    }
+
+    pub fn fill_circle(&mut self, cx: f32, cy: f32, r: f32, paint: impl Paint) {
+        if r <= 0.0 {
+            return;
+        }
+        let Some(b) = self.bounds(cx - r, cy - r, cx + r, cy + r) else {
+            return;
+        };
+        self.shade(&b, paint, |px, py| coverage(r - (px - cx).hypot(py - cy)));
+    }
+
+    /// Circle outline centered on radius `r`.
+    pub fn stroke_circle(&mut self, cx: f32, cy: f32, r: f32, thickness: f32, paint: impl Paint) {
+        if r <= 0.0 {
+            return;
+        }
+        let half = thickness / 2.0;
+        let outer = r + half;
+        let Some(b) = self.bounds(cx - outer, cy - outer, cx + outer, cy + outer) else {
+            return;
+        };
+        self.shade(&b, paint, |px, py| coverage(half - ((px - cx).hypot(py - cy) - r).abs()));
+    }
+
+    /// Arc from `start` sweeping `sweep` radians (positive = clockwise on
+    /// screen, 0 = 3 o'clock) with round caps.
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_arc(&mut self, cx: f32, cy: f32, r: f32, start: f32, sweep: f32, thickness: f32, paint: impl Paint) {
+        if r <= 0.0 || sweep == 0.0 {
+            return;
+        }
+        if sweep.abs() >= std::f32::consts::TAU {
+            self.stroke_circle(cx, cy, r, thickness, paint);
+            return;
+        }
+        let half = thickness / 2.0;
+        let outer = r + half;
+        let Some(b) = self.bounds(cx - outer, cy - outer, cx + outer, cy + outer) else {
+            return;
+        };
+
+        // Normalize to a positive sweep starting at `from`
+        let (from, sweep) = if sweep < 0.0 { (start + sweep, -sweep) } else { (start, sweep) };
+        let (ax, ay) = (cx + r * from.cos(), cy + r * from.sin());
+        let (bx, by) = (cx + r * (from + sweep).cos(), cy + r * (from + sweep).sin());
+
+        self.shade(&b, paint, |px, py| {
+            let (dx, dy) = (px - cx, py - cy);
+            let rel = (dy.atan2(dx) - from).rem_euclid(std::f32::consts::TAU);
+            let d = if rel <= sweep {
+                (dx.hypot(dy) - r).abs()
+            } else {
+                (px - ax).hypot(py - ay).min((px - bx).hypot(py - by))
+            };
+            coverage(half - d)
+        });
+    }
+
+    /// Rectangle with corner radius `radius` (clamped to half the short side).
+    pub fn fill_rounded_rect(&mut self, x: f32, y: f32, w: f32, h: f32, radius: f32, paint: impl Paint) {
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+        let Some(b) = self.bounds(x, y, x + w, y + h) else {
+            return;
+        };
+        let radius = radius.clamp(0.0, w.min(h) / 2.0);
+        let (cx, cy) = (x + w / 2.0, y + h / 2.0);
+        let (hx, hy) = (w / 2.0 - radius, h / 2.0 - radius);
+
+        self.shade(&b, paint, |px, py| {
+            // Signed distance to a rounded box
+            let qx = (px - cx).abs() - hx;
+            let qy = (py - cy).abs() - hy;
+            let outside = qx.max(0.0).hypot(qy.max(0.0));
+            let inside = qx.max(qy).min(0.0);
+            coverage(-(outside + inside - radius))
+        });
+    }
+
+    /// Filled polygon (non-zero winding). Coverage is exact horizontally and
+    /// sampled on `POLY_SUBSAMPLES` sub-scanlines vertically.
+    pub fn fill_polygon(&mut self, points: &[(f32, f32)], paint: impl Paint) {
+        if points.len() < 3 {
+            return;
+        }
+        let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+        let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+        for &(x, y) in points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let Some(b) = self.bounds(min_x, min_y, max_x, max_y) else {
+            return;
+        };
+
+        let row_w = (b.x1 - b.x0) as usize;
+        let mut acc = vec![0.0f32; row_w];
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        let weight = 1.0 / POLY_SUBSAMPLES as f32;
+
+        for py in b.y0..b.y1 {
+            acc.fill(0.0);
+            for s in 0..POLY_SUBSAMPLES {
+                let sy = py as f32 + (s as f32 + 0.5) * weight;
+
+                crossings.clear();
+                for (i, &(ax, ay)) in points.iter().enumerate() {
+                    let (bx, by) = points[(i + 1) % points.len()];
+                    if (ay <= sy) == (by <= sy) {
+                        continue;
+                    }
+                    let t = (sy - ay) / (by - ay);
+                    let dir = if by > ay { 1 } else { -1 };
+                    crossings.push((ax + (bx - ax) * t, dir));
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+                for pair in crossings.windows(2) {
+                    winding += pair[0].1;
+                    if winding != 0 {
+                        add_span(&mut acc, pair[0].0 - b.x0 as f32, pair[1].0 - b.x0 as f32, weight);
+                    }
+                }
+            }
+            for (i, &c) in acc.iter().enumerate() {
+                if c > 0.0 {
+                    let px = b.x0 + i as i32;
+                    self.blend(px, py, paint.color_at(px as f32 + 0.5, py as f32 + 0.5), c);
+                }
+            }
+        }
+    }
+
+    /// Source-over blend of `color` at `coverage` (0..1) onto one pixel.
+    #[inline]
+    pub fn blend(&mut self, x: i32, y: i32, color: u32, coverage: f32) {
+        if coverage <= 0.0 || x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return;
+        }
+        let idx = (y * self.width + x) as usize;
+        self.pixels[idx] = blend_over(self.pixels[idx], color, coverage);
+    }
+
+    /// Integer pixel bounds for a float box, padded one pixel for the AA
+    /// fringe and clipped to the canvas. `None` when fully off-canvas.
+    fn bounds(&self, x0: f32, y0: f32, x1: f32, y1: f32) -> Option<Bounds> {
+        let b = Bounds {
+            x0: ((x0 - 1.0).floor() as i32).max(0),
+            y0: ((y0 - 1.0).floor() as i32).max(0),
+            x1: ((x1 + 1.0).ceil() as i32).min(self.width),
+            y1: ((y1 + 1.0).ceil() as i32).min(self.height),
+        };
+        (b.x0 < b.x1 && b.y0 < b.y1).then_some(b)
+    }
+
+    /// Blend `paint` over every pixel in `b`, weighted by `cov(center_x, center_y)`.
+    fn shade(&mut self, b: &Bounds, paint: impl Paint, cov: impl Fn(f32, f32) -> f32) {
+        for py in b.y0..b.y1 {
+            let fy = py as f32 + 0.5;
+            for px in b.x0..b.x1 {
+                let fx = px as f32 + 0.5;
+                let c = cov(fx, fy);
+                if c > 0.0 {
+                    self.blend(px, py, paint.color_at(fx, fy), c);
+                }
+            }
+        }
+    }
+}
+
+/// Map signed distance inside an edge (pixels) to a 0..1 coverage estimate.
+#[inline]
+fn coverage(inside: f32) -> f32 {
+    (inside + 0.5).clamp(0.0, 1.0)
+}
+
+/// Distance from `(px, py)` to the segment `a`-`b`.
+#[inline]
+fn segment_distance(px: f32, py: f32, ax: f32, ay: f32, bx: f32, by: f32) -> f32 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((px - ax) * dx + (py - ay) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (px - (ax + dx * t)).hypot(py - (ay + dy * t))
+}
+
+/// Add horizontal coverage for `[x0, x1)` into `acc`, splitting partial end pixels.
+fn add_span(acc: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let len = acc.len() as f32;
+    let (x0, x1) = (x0.clamp(0.0, len), x1.clamp(0.0, len));
+    if x1 <= x0 {
+        return;
+    }
+    let i0 = x0.floor() as usize;
+    let i1 = x1.floor() as usize;
+    if i0 == i1 {
+        acc[i0] += (x1 - x0) * weight;
+        return;
+    }
+    acc[i0] += (i0 as f32 + 1.0 - x0) * weight;
+    for a in &mut acc[i0 + 1..i1] {
+        *a += weight;
+    }
+    if i1 < acc.len() {
+        acc[i1] += (x1 - i1 as f32) * weight;
+    }
+}
+
+/// Straight-alpha source-over of `src` (scaled by `coverage`) onto `dst`.
+#[inline]
+pub fn blend_over(dst: u32, src: u32, coverage: f32) -> u32 {
+    let sa = ((src >> 24) as f32 / 255.0) * coverage.min(1.0);
+    if sa <= 0.0 {
+        return dst;
+    }
+    if sa >= 1.0 {
+        return src;
+    }
+    let da = (dst >> 24) as f32 / 255.0;
+    let out_a = sa + da * (1.0 - sa);
+    let dst_w = da * (1.0 - sa);
+
+    let channel = |shift: u32| -> u32 {
+        let s = ((src >> shift) & 0xFF) as f32;
+        let d = ((dst >> shift) & 0xFF) as f32;
+        (((s * sa + d * dst_w) / out_a).round() as u32).min(255) << shift
+    };
+
+    channel(0) | channel(8) | channel(16) | (((out_a * 255.0).round() as u32) << 24)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: u32 = 0xFF0000FF;
+
+    fn alpha_at(pixels: &[u32], width: i32, x: i32, y: i32) -> u32 {
+        pixels[(y * width + x) as usize] >> 24
+    }
+
+    #[test]
+    fn blend_over_transparent_keeps_color_and_scales_alpha() {
+        let out = blend_over(0, RED, 0.5);
+        assert_eq!(out & 0x00FFFFFF, RED & 0x00FFFFFF);
+        assert_eq!(out >> 24, 128);
+    }
+
+    #[test]
+    fn circle_is_solid_inside_and_soft_at_edge() {
+        let (w, h) = (40, 40);
+        let mut pixels = vec![0u32; (w * h) as usize];
+        Canvas::new(&mut pixels, w, h).fill_circle(20.0, 20.0, 10.0, RED);
+
+        assert_eq!(alpha_at(&pixels, w, 20, 20), 255);
+        assert_eq!(alpha_at(&pixels, w, 2, 2), 0);
+        let edge = alpha_at(&pixels, w, 29, 20);
+        assert!(edge > 0 && edge < 255, "edge alpha {}", edge);
+    }
+
+    #[test]
+    fn polygon_coverage_matches_area() {
+        // Right triangle covering half of a 10x10 square
+        let (w, h) = (16, 16);
+        let mut pixels = vec![0u32; (w * h) as usize];
+        Canvas::new(&mut pixels, w, h).fill_polygon(&[(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)], RED);
+
+        let total: f32 = pixels.iter().map(|p| (p >> 24) as f32 / 255.0).sum();
+        assert!((total - 50.0).abs() < 1.0, "covered area {}", total);
+    }
+
+    #[test]
+    fn shapes_clip_at_canvas_edges() {
+        let (w, h) = (8, 8);
+        let mut pixels = vec![0u32; (w * h) as usize];
+        let mut canvas = Canvas::new(&mut pixels, w, h);
+        canvas.line(-20.0, 4.0, 40.0, 4.0, 3.0, RED);
+        canvas.fill_rounded_rect(-5.0, -5.0, 30.0, 30.0, 4.0, RED);
+        canvas.stroke_arc(4.0, 4.0, 50.0, 0.0, 3.0, 2.0, RED);
+        assert_eq!(alpha_at(&pixels, w, 4, 4), 255);
+    }
+}
@@ -0,0 +1,112 @@
+//! Scrolling spectrogram history.
+//!
+//! Unlike the per-band `physics_state`, the spectrogram keeps a ring of past
+//! spectra. Rows are pushed on a fixed clock (`ROWS_PER_SEC`), not per render
+//! call, so scroll speed is the same at any frame rate.
+
+use crate::palette::Ramp;
+use crate::raster::Canvas;
+
+/// Frequency columns per history row.
+pub const COLUMNS: usize = 128;
+/// History rows recorded per second of audio.
+pub const ROWS_PER_SEC: f32 = 60.0;
+/// Upper bound on history length, whatever `spectrogram_seconds` asks for.
+const MAX_ROWS: usize = 1200;
+
+#[derive(Default)]
+pub struct Spectrogram {
+    /// `capacity` rows of `COLUMNS` levels; row `head` is the newest.
+    rows: Vec<u8>,
+    capacity: usize,
+    head: usize,
+    len: usize,
+    /// Seconds accumulated toward the next row.
+    pending: f32,
+}
+
+/// Where and how to paint the waterfall.
+pub struct Panel {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+    /// Multiplier on stored levels before color mapping.
+    pub gain: f32,
+}
+
+impl Spectrogram {
+    /// Record `dt` seconds of `freq_data`, resizing history to `seconds`.
+    pub fn advance(&mut self, freq_data: &[u8], dt: f32, seconds: f32) {
+        let capacity = ((seconds * ROWS_PER_SEC).round() as usize).clamp(1, MAX_ROWS);
+        if capacity != self.capacity {
+            self.rows = vec![0; capacity * COLUMNS];
+            self.capacity = capacity;
+            self.head = 0;
+            self.len = 0;
+        }
+
+        self.pending += dt;
+        let step = 1.0 / ROWS_PER_SEC;
+        if self.pending < step {
+            return;
+        }
+
+        let mut row = [0u8; COLUMNS];
+        sample_columns(freq_data, &mut row);
+
+        // A long frame (e.g. 30fps export) pushes several identical rows
+        while self.pending >= step {
+            self.pending -= step;
+            self.head = (self.head + 1) % self.capacity;
+            self.rows[self.head * COLUMNS..(self.head + 1) * COLUMNS].copy_from_slice(&row);
+            self.len = (self.len + 1).min(self.capacity);
+        }
+    }
+
+    /// Waterfall: low frequencies left, newest row at the top, scrolling down.
+    /// Quiet cells stay transparent so the background shows through.
+    pub fn draw(&self, canvas: &mut Canvas, panel: &Panel, ramp: &Ramp) {
+        if self.len == 0 || panel.width <= 0 || panel.height <= 0 {
+            return;
+        }
+
+        let columns: Vec<usize> = (0..panel.width)
+            .map(|x| (x as usize * COLUMNS / panel.width as usize).min(COLUMNS - 1))
+            .collect();
+
+        for y in 0..panel.height {
+            let age = y as usize * self.capacity / panel.height as usize;
+            if age >= self.len {
+                break;
+            }
+            let row_idx = (self.head + self.capacity - age) % self.capacity;
+            let row = &self.rows[row_idx * COLUMNS..(row_idx + 1) * COLUMNS];
+
+            for (x, &c) in columns.iter().enumerate() {
+                let level = (row[c] as f32 / 255.0 * panel.gain).min(1.0);
+                if level <= 0.0 {
+                    continue;
+                }
+                canvas.blend(panel.left + x as i32, panel.top + y, ramp.sample(level), level.sqrt());
+            }
+        }
+    }
+}
+
+/// Average `freq_data` into `COLUMNS` log-spaced columns (bin 1 to the last bin).
+fn sample_columns(freq_data: &[u8], out: &mut [u8; COLUMNS]) {
+    if freq_data.len() < 2 {
+        out.fill(0);
+        return;
+    }
+    let last = (freq_data.len() - 1) as f32;
+    let edge = |c: usize| last.powf(c as f32 / COLUMNS as f32) as usize;
+
+    for (c, level) in out.iter_mut().enumerate() {
+        let lo = edge(c).max(1);
+        let hi = edge(c + 1).clamp(lo + 1, freq_data.len());
+        let bins = &freq_data[lo..hi];
+        *level = (bins.iter().map(|&b| b as u32).sum::<u32>() / bins.len() as u32) as u8;
+    }
+}
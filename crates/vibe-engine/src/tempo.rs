@@ -0,0 +1,262 @@
+//! Offline tempo estimation and the beat grid it produces.
+//!
+//! Export has the whole signal up front, so instead of reacting to onsets it
+//! can fit one constant tempo per track: an onset envelope (positive log
+//! energy change, full band plus a low-passed band) is autocorrelated to pick
+//! the beat period, then a comb over the whole track refines tempo and phase
+//! so the grid stays locked to the audio minutes later.
+
+/// Onset envelope frames per second (approximate; the hop is whole samples).
+const ENV_RATE: f64 = 200.0;
+/// Low band cutoff for the kick-weighted envelope (Hz).
+const LOW_CUTOFF_HZ: f32 = 150.0;
+/// Energy floor (about -60 dB) so near-silence doesn't produce onsets.
+const ENERGY_FLOOR: f32 = 1e-6;
+const MIN_BPM: f64 = 60.0;
+const MAX_BPM: f64 = 200.0;
+/// Center of the tempo prior; resolves half/double-time ambiguity.
+const PRIOR_BPM: f64 = 120.0;
+/// Prior width in octaves.
+const PRIOR_OCTAVES: f64 = 1.0;
+/// Normalized autocorrelation below which no tempo is reported.
+const MIN_CONFIDENCE: f32 = 0.1;
+/// Shortest signal worth analyzing (seconds).
+const MIN_SECS: f64 = 5.0;
+/// Beats per bar, for downbeat accents.
+const BEATS_PER_BAR: i64 = 4;
+/// Grid beat strength off the downbeat (the downbeat is 1.0).
+const OFFBEAT_STRENGTH: f32 = 0.6;
+
+/// Constant tempo fitted to one stretch of audio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tempo {
+    pub bpm: f32,
+    /// Seconds from the start of the analyzed samples to the first beat
+    /// (less than one beat period).
+    pub first_beat: f64,
+}
+
+/// A beat grid covering `start..end` seconds of the render timeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoSection {
+    pub start: f64,
+    pub end: f64,
+    pub bpm: f32,
+    /// Absolute time (seconds) of a beat; the grid extends both ways.
+    pub first_beat: f64,
+}
+
+/// Estimate tempo and beat phase of mono `samples`. `None` for short or
+/// arrhythmic audio.
+pub fn estimate_tempo(samples: &[f32], sample_rate: u32) -> Option<Tempo> {
+    if sample_rate == 0 {
+        return None;
+    }
+    let hop = (sample_rate as f64 / ENV_RATE).round().max(1.0) as usize;
+    // Exact frame rate, so rounding the hop doesn't skew the tempo
+    let rate = sample_rate as f64 / hop as f64;
+
+    let envelope = onset_envelope(samples, sample_rate, hop);
+    if (envelope.len() as f64) < MIN_SECS * rate {
+        return None;
+    }
+
+    let coarse = autocorrelation_bpm(&envelope, rate)?;
+    let (bpm, phase) = comb_refine(&envelope, rate, coarse);
+
+    Some(Tempo {
+        bpm: bpm as f32,
+        first_beat: phase / rate,
+    })
+}
+
+/// One value per hop: rise in log energy, full band plus low band.
+fn onset_envelope(samples: &[f32], sample_rate: u32, hop: usize) -> Vec<f32> {
+    let k = 1.0 - (-std::f32::consts::TAU * LOW_CUTOFF_HZ / sample_rate as f32).exp();
+    let mut low = 0.0;
+    let mut prev: Option<(f32, f32)> = None;
+
+    samples
+        .chunks(hop)
+        .map(|chunk| {
+            let (mut full, mut bass) = (0.0, 0.0);
+            for &s in chunk {
+                low += (s - low) * k;
+                full += s * s;
+                bass += low * low;
+            }
+            let n = chunk.len() as f32;
+            let level = ((full / n + ENERGY_FLOOR).ln(), (bass / n + ENERGY_FLOOR).ln());
+            let onset = prev.map_or(0.0, |p| (level.0 - p.0).max(0.0) + (level.1 - p.1).max(0.0));
+            prev = Some(level);
+            onset
+        })
+        .collect()
+}
+
+/// Best beat period by prior-weighted autocorrelation, as BPM.
+fn autocorrelation_bpm(envelope: &[f32], rate: f64) -> Option<f64> {
+    let mean = envelope.iter().sum::<f32>() / envelope.len() as f32;
+    let centered: Vec<f32> = envelope.iter().map(|v| v - mean).collect();
+    let n = centered.len();
+
+    let energy = centered.iter().map(|v| v * v).sum::<f32>() / n as f32;
+    if energy <= 0.0 {
+        return None;
+    }
+
+    let min_lag = (60.0 * rate / MAX_BPM).floor() as usize;
+    let max_lag = ((60.0 * rate / MIN_BPM).ceil() as usize).min(n - 1);
+    let mut best: Option<(f64, f32, f64)> = None; // (score, correlation, bpm)
+
+    for lag in min_lag.max(1)..=max_lag {
+        let r = centered[..n - lag]
+            .iter()
+            .zip(&centered[lag..])
+            .map(|(a, b)| a * b)
+            .sum::<f32>()
+            / (n - lag) as f32
+            / energy;
+        let bpm = 60.0 * rate / lag as f64;
+        let octaves = (bpm / PRIOR_BPM).log2() / PRIOR_OCTAVES;
+        let score = r as f64 * (-0.5 * octaves * octaves).exp();
+        if best.map_or(true, |(s, _, _)| score > s) {
+            best = Some((score, r, bpm));
+        }
+    }
+
+    best.filter(|&(_, r, _)| r >= MIN_CONFIDENCE).map(|(_, _, bpm)| bpm)
+}
+
+/// Refine `coarse` BPM (±3%, then ±0.1%) and find the beat phase, by the
+/// mean envelope under a comb of beats spanning the whole signal.
+/// Returns (bpm, phase in frames).
+fn comb_refine(envelope: &[f32], rate: f64, coarse: f64) -> (f64, f64) {
+    let mut best = (f64::MIN, coarse, 0.0);
+    for (span, steps) in [(0.03, 30), (0.001, 20)] {
+        let center = best.1;
+        for i in -steps..=steps {
+            let bpm = center * (1.0 + span * i as f64 / steps as f64);
+            let (score, phase) = best_phase(envelope, 60.0 * rate / bpm);
+            if score > best.0 {
+                best = (score, bpm, phase);
+            }
+        }
+    }
+    (best.1, best.2)
+}
+
+/// Highest-scoring comb offset in `0..period` frames, and its score.
+fn best_phase(envelope: &[f32], period: f64) -> (f64, f64) {
+    let comb = |offset: f64| {
+        let (mut sum, mut count) = (0.0, 0);
+        let mut t = offset;
+        while t < (envelope.len() - 1) as f64 {
+            let i = t as usize;
+            let f = (t - i as f64) as f32;
+            sum += (envelope[i] * (1.0 - f) + envelope[i + 1] * f) as f64;
+            count += 1;
+            t += period;
+        }
+        sum / count.max(1) as f64
+    };
+
+    let mut best = (f64::MIN, 0.0);
+    for offset in 0..period.ceil() as usize {
+        let score = comb(offset as f64);
+        if score > best.0 {
+            best = (score, offset as f64);
+        }
+    }
+    best
+}
+
+/// Beat grids for the render timeline, one section per track.
+#[derive(Default)]
+pub struct TempoMap {
+    sections: Vec<TempoSection>,
+    /// Beats elapsed at the last `advance`, if inside a section.
+    phase: Option<f64>,
+}
+
+impl TempoMap {
+    pub fn set(&mut self, sections: Vec<TempoSection>) {
+        self.sections = sections;
+        self.phase = None;
+    }
+
+    pub fn phase(&self) -> Option<f64> {
+        self.phase
+    }
+
+    /// Move to `time`, `dt` seconds after the previous frame. `None` when no
+    /// section covers `time`; otherwise the strength of a grid beat crossed
+    /// since the previous frame (1.0 on the downbeat), or 0.0 between beats.
+    pub fn advance(&mut self, time: f64, dt: f32) -> Option<f32> {
+        let Some(section) = self.sections.iter().find(|s| time >= s.start && time < s.end) else {
+            self.phase = None;
+            return None;
+        };
+
+        let beats_per_sec = section.bpm as f64 / 60.0;
+        let phase = (time - section.first_beat) * beats_per_sec;
+        // Tolerance keeps f32 `dt` error from re-crossing the beat just passed
+        let previous = phase - dt as f64 * beats_per_sec + 1e-6;
+        self.phase = Some(phase);
+
+        if previous.floor() < phase.floor() {
+            let beat = phase.floor() as i64;
+            Some(if beat.rem_euclid(BEATS_PER_BAR) == 0 { 1.0 } else { OFFBEAT_STRENGTH })
+        } else {
+            Some(0.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 44_100;
+
+    /// Decaying 60 Hz kicks at `bpm`, starting `offset` seconds in, over quiet noise.
+    fn click_track(bpm: f64, offset: f64, secs: f64) -> Vec<f32> {
+        let period = 60.0 / bpm;
+        let mut seed = 1u32;
+        (0..(secs * RATE as f64) as usize)
+            .map(|i| {
+                let t = i as f64 / RATE as f64;
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = (seed >> 8) as f32 / (1 << 24) as f32 - 0.5;
+                let since = (t - offset).rem_euclid(period);
+                let kick = if t >= offset { (-since * 30.0).exp() * (since * 60.0 * std::f64::consts::TAU).sin() } else { 0.0 };
+                kick as f32 * 0.8 + noise * 0.01
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_tempo_and_phase_of_a_click_track() {
+        let tempo = estimate_tempo(&click_track(128.0, 0.2, 20.0), RATE).expect("tempo");
+        assert!((tempo.bpm - 128.0).abs() < 0.1, "bpm {}", tempo.bpm);
+        assert!((tempo.first_beat - 0.2).abs() < 0.015, "first beat {}", tempo.first_beat);
+    }
+
+    #[test]
+    fn silence_has_no_tempo() {
+        assert_eq!(estimate_tempo(&vec![0.0; RATE as usize * 10], RATE), None);
+    }
+
+    #[test]
+    fn grid_marks_downbeats() {
+        let mut map = TempoMap::default();
+        map.set(vec![TempoSection { start: 0.0, end: 10.0, bpm: 120.0, first_beat: 0.0 }]);
+        let dt = 1.0 / 30.0;
+        let strengths: Vec<f32> = (1..=60)
+            .filter_map(|frame| map.advance(frame as f64 / 30.0, dt))
+            .filter(|&s| s > 0.0)
+            .collect();
+        assert_eq!(strengths, vec![0.6, 0.6, 0.6, 1.0]);
+        assert_eq!(map.advance(12.0, dt), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Golden-frame tests: render synthetic audio through `render_stereo_native`
+//! at fixed sizes and compare against the PNGs in `tests/golden/`.
+//!
+//! Comparison is `vibe_golden::assert_golden`: on mismatch the actual frame
+//! and a diff are written under `target/tmp/golden/`. After an intended
+//! visual change, regenerate with `UPDATE_GOLDEN=1 cargo test --test golden`
+//! and review the new PNGs.
+
+use std::f32::consts::TAU;
+use std::path::Path;
+
+use serde_json::json;
+use vibe_engine::{VibeEngine, VibeSettings, FFT_SIZE};
+
+const SAMPLE_RATE: f32 = 48_000.0;
+const FPS: f64 = 30.0;
+/// Frames rendered before the snapshot, so physics and history settle.
+const FRAMES: usize = 45;
+
+/// Kick on every half second plus a chord: a 110/440 Hz pair in both
+/// channels and a 2.5 kHz tone on the right only, so stereo views have width.
+fn synthetic_audio(secs: f32) -> (Vec<f32>, Vec<f32>) {
+    (0..(secs * SAMPLE_RATE) as usize)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE;
+            let since = t % 0.5;
+            let kick = (-since * 12.0).exp() * (TAU * 55.0 * since).sin() * 0.6;
+            let chord = (TAU * 110.0 * t).sin() * 0.15 + (TAU * 440.0 * t).sin() * 0.08;
+            let side = (TAU * 2500.0 * t).sin() * 0.25;
+            (kick + chord, kick * 0.9 + chord + side)
+        })
+        .unzip()
+}
+
+fn render(width: i32, height: i32, settings: serde_json::Value) -> Vec<u8> {
+    let mut base = json!({
+        "visualizer_mode": "Bars",
+        "visualizer_color": "#06b6d4",
+        "visualizer_intensity": 0.8,
+    });
+    base.as_object_mut().unwrap().extend(settings.as_object().unwrap().clone());
+    let settings: VibeSettings = serde_json::from_value(base).expect("settings");
+
+    let (left, right) = synthetic_audio(2.0);
+    let mut engine = VibeEngine::new(width, height);
+    engine.set_sample_rate(SAMPLE_RATE);
+    for frame in 0..FRAMES {
+        let time = frame as f64 / FPS;
+        let end = (time * SAMPLE_RATE as f64) as usize;
+        let window = end.saturating_sub(FFT_SIZE)..end;
+        engine.render_stereo_native(&settings, &left[window.clone()], &right[window], time);
+    }
+    engine.get_pixel_slice().to_vec()
+}
+
+fn assert_golden(name: &str, width: u32, height: u32, actual: &[u8]) {
+    vibe_golden::assert_golden(Path::new(env!("CARGO_MANIFEST_DIR")), name, width, height, actual);
+}
+
+fn check(name: &str, width: i32, height: i32, settings: serde_json::Value) {
+    let pixels = render(width, height, settings);
+    assert_golden(name, width as u32, height as u32, &pixels);
+}
+
+#[test]
+fn bars() {
+    check("bars_landscape", 320, 180, json!({}));
+    check("bars_portrait", 180, 320, json!({ "bar_anchor": "BottomCenter" }));
+    check("bars_mirrored", 320, 180, json!({ "bar_layout": "Mirrored", "bar_anchor": "FullWidth" }));
+    check("bars_stereo", 320, 180, json!({ "bar_layout": "Stereo", "bar_anchor": "FullWidth", "bar_count": 32 }));
+}
+
+#[test]
+fn orbital() {
+    check("orbital", 240, 240, json!({ "visualizer_mode": "Orbital" }));
+}
+
+#[test]
+fn wave() {
+    check("wave", 320, 180, json!({ "visualizer_mode": "Wave" }));
+    check("wave_stereo", 320, 180, json!({ "visualizer_mode": "Wave", "wave_layout": "Stereo" }));
+}
+
+#[test]
+fn spectrogram() {
+    check("spectrogram", 320, 180, json!({ "visualizer_mode": "Spectrogram" }));
+}
+
+#[test]
+fn particles() {
+    check("particles", 320, 180, json!({ "visualizer_mode": "Particles", "particle_seed": 7 }));
+}
+
+#[test]
+fn goniometer() {
+    check("goniometer", 240, 240, json!({ "visualizer_mode": "Goniometer" }));
+}
+
+#[test]
+fn palette_and_glow() {
+    let palette = json!({
+        "kind": "Vertical",
+        "stops": [{ "offset": 0, "color": "#06b6d4" }, { "offset": 1, "color": "#ec4899" }],
+    });
+    check(
+        "bars_palette_glow",
+        320,
+        180,
+        json!({ "visualizer_palette": palette, "glow_strength": 0.8, "bar_anchor": "BottomCenter" }),
+    );
+}
@@ -0,0 +1,376 @@
+//! Animated backgrounds: GIF, APNG and animated WebP frames played on a
+//! timeline, or a video streamed from FFmpeg as it plays; either loops or
+//! ping-pongs for the length of the export.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, DynamicImage, Frames, ImageFormat, ImageReader, RgbaImage};
+
+/// Animation frames held past this many bytes, decoded and fitted together,
+/// fail the export instead of exhausting memory. Streamed video caches up
+/// to this many (about 2 seconds of 1080p at 30 fps).
+pub const MAX_FRAME_BYTES: usize = 512 << 20;
+
+/// Browsers play GIF delays this short at 100 ms; match them.
+const MIN_DELAY_MS: f64 = 20.0;
+const DEFAULT_DELAY_MS: f64 = 100.0;
+
+/// How an animated background repeats when shorter than the export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Playback {
+    #[default]
+    Loop,
+    /// Forward then backward, without repeating the end frames.
+    PingPong,
+}
+
+/// What a background file decoded to.
+pub enum Source {
+    Still(DynamicImage),
+    /// Full-canvas frames with their display times in seconds.
+    Animated { frames: Vec<RgbaImage>, delays: Vec<f64> },
+    /// Not an image format; decoded as a video by FFmpeg.
+    Video,
+}
+
+/// Decode the background at `path`, counting animation frames against
+/// `budget`. Animations of a single frame are stills.
+pub fn open(path: &Path, budget: &mut FrameBudget) -> Result<Source, String> {
+    let load_err = |e: image::ImageError| format!("image load failed: {}", e);
+    let file = || {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| format!("image load failed: {}", e))
+    };
+
+    let reader = ImageReader::new(file()?)
+        .with_guessed_format()
+        .map_err(|e| format!("image load failed: {}", e))?;
+    let format = reader.format();
+    match format {
+        None => Ok(Source::Video),
+        Some(ImageFormat::Gif) => animated(GifDecoder::new(file()?).map_err(load_err)?.into_frames(), budget),
+        Some(ImageFormat::Png) => {
+            let decoder = PngDecoder::new(file()?).map_err(load_err)?;
+            if decoder.is_apng().map_err(load_err)? {
+                animated(decoder.apng().map_err(load_err)?.into_frames(), budget)
+            } else {
+                reader.decode().map(Source::Still).map_err(load_err)
+            }
+        }
+        Some(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(file()?).map_err(load_err)?;
+            if decoder.has_animation() {
+                animated(decoder.into_frames(), budget)
+            } else {
+                reader.decode().map(Source::Still).map_err(load_err)
+            }
+        }
+        Some(_) => reader.decode().map(Source::Still).map_err(load_err),
+    }
+}
+
+fn animated(frames: Frames, budget: &mut FrameBudget) -> Result<Source, String> {
+    let mut images = Vec::new();
+    let mut delays = Vec::new();
+    for frame in frames {
+        let frame = frame.map_err(|e| format!("image load failed: {}", e))?;
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let ms = numer as f64 / denom.max(1) as f64;
+        delays.push(if ms < MIN_DELAY_MS { DEFAULT_DELAY_MS } else { ms } / 1000.0);
+
+        let image = frame.into_buffer();
+        budget.take(image.as_raw().len())?;
+        images.push(image);
+    }
+
+    match images.len() {
+        0 => Err("image load failed: animation has no frames".into()),
+        1 => Ok(Source::Still(DynamicImage::ImageRgba8(images.remove(0)))),
+        _ => Ok(Source::Animated { frames: images, delays }),
+    }
+}
+
+/// Bytes of background frames held at once. Decoding and fitting share
+/// one budget, so a clip can't hold `MAX_FRAME_BYTES` in each stage.
+#[derive(Debug, Default)]
+pub struct FrameBudget {
+    bytes: usize,
+}
+
+impl FrameBudget {
+    /// Count `bytes` more; fails once the total passes `MAX_FRAME_BYTES`.
+    pub fn take(&mut self, bytes: usize) -> Result<(), String> {
+        self.bytes += bytes;
+        if self.bytes > MAX_FRAME_BYTES {
+            return Err(format!(
+                "background animation too large (over {} MiB decoded); use a shorter or smaller clip",
+                MAX_FRAME_BYTES >> 20
+            ));
+        }
+        Ok(())
+    }
+
+    /// `bytes` taken earlier were freed.
+    pub fn release(&mut self, bytes: usize) {
+        self.bytes = self.bytes.saturating_sub(bytes);
+    }
+}
+
+/// Which frame of an animation shows at a given video time.
+pub struct Timeline {
+    /// Frame indices in play order for one cycle.
+    order: Vec<usize>,
+    /// End time (seconds) of each entry in `order`.
+    ends: Vec<f64>,
+}
+
+impl Timeline {
+    /// `delays` are the frames' display times in seconds.
+    pub fn new(delays: &[f64], playback: Playback) -> Self {
+        let mut order: Vec<usize> = (0..delays.len()).collect();
+        if playback == Playback::PingPong && delays.len() > 2 {
+            order.extend((1..delays.len() - 1).rev());
+        }
+        let mut end = 0.0;
+        let ends = order
+            .iter()
+            .map(|&i| {
+                end += delays[i].max(0.0);
+                end
+            })
+            .collect();
+        Self { order, ends }
+    }
+
+    pub fn frame_at(&self, time_secs: f64) -> usize {
+        let duration = self.ends.last().copied().unwrap_or(0.0);
+        if duration <= 0.0 {
+            return self.order.first().copied().unwrap_or(0);
+        }
+        let t = time_secs.rem_euclid(duration);
+        let i = self.ends.partition_point(|&end| end <= t);
+        self.order[i.min(self.order.len() - 1)]
+    }
+}
+
+/// Frames decoded in order from any start frame, e.g. a video through
+/// FFmpeg.
+pub trait FrameStream {
+    type Frame;
+
+    /// Restart decoding at frame `start`.
+    fn seek(&mut self, start: usize) -> Result<(), String>;
+
+    /// Next frame; `None` at the end of the clip.
+    fn next_frame(&mut self) -> Result<Option<Self::Frame>, String>;
+
+    /// Memory held by `frame`.
+    fn frame_bytes(frame: &Self::Frame) -> usize;
+}
+
+/// Plays a `FrameStream` as it's needed, one frame per video frame, caching
+/// recent frames up to `MAX_FRAME_BYTES`. A clip that fits is decoded once;
+/// a longer one is decoded again each time round, its ping-pong reverse
+/// pass a cache-sized chunk at a time.
+pub struct StreamPlayer<S: FrameStream> {
+    stream: S,
+    playback: Playback,
+    /// Index of the frame `stream` yields next; `None` until seeked and
+    /// once ended.
+    next: Option<usize>,
+    /// Frames in the clip, known once its end has been read.
+    len: Option<usize>,
+    /// Decoded frames with their indices, oldest first.
+    cache: VecDeque<(usize, S::Frame)>,
+    /// Most frames `cache` holds, from the first frame's size.
+    capacity: Option<usize>,
+    /// Index of the frame shown last, to tell a reverse pass and for
+    /// `current`.
+    last: usize,
+}
+
+impl<S: FrameStream> StreamPlayer<S> {
+    pub fn new(stream: S, playback: Playback) -> Self {
+        Self { stream, playback, next: None, len: None, cache: VecDeque::new(), capacity: None, last: 0 }
+    }
+
+    /// Decode the frame shown `position` frames into the export, for
+    /// `current`.
+    pub fn advance(&mut self, position: usize) -> Result<(), String> {
+        loop {
+            let index = self.index(position);
+            if self.cache.iter().any(|(cached, _)| *cached == index) {
+                self.last = index;
+                return Ok(());
+            }
+
+            let next = match self.next {
+                // Reading on reaches `index`
+                Some(next) if next <= index => next,
+                _ => {
+                    let start = match (self.len, self.capacity) {
+                        // Reverse pass: the chunk that ends at `index`
+                        (Some(_), Some(capacity)) if index < self.last => {
+                            (index + 1).saturating_sub(capacity)
+                        }
+                        (Some(_), _) => index,
+                        // End not seen yet, so `index` may be past it
+                        (None, _) => 0,
+                    };
+                    self.stream.seek(start)?;
+                    start
+                }
+            };
+            match self.stream.next_frame()? {
+                Some(frame) => {
+                    let capacity = *self
+                        .capacity
+                        .get_or_insert_with(|| (MAX_FRAME_BYTES / S::frame_bytes(&frame).max(1)).max(1));
+                    self.cache.retain(|(cached, _)| *cached != next);
+                    self.cache.push_back((next, frame));
+                    if self.cache.len() > capacity {
+                        self.cache.pop_front();
+                    }
+                    self.next = Some(next + 1);
+                }
+                None if next == 0 => return Err("background video has no frames".into()),
+                // A seek past the end also shortens a known length
+                None => {
+                    self.len = Some(next);
+                    self.next = None;
+                }
+            }
+        }
+    }
+
+    /// Frame found by the last `advance`. Panics before the first.
+    pub fn current(&self) -> &S::Frame {
+        self.cache
+            .iter()
+            .find(|(cached, _)| *cached == self.last)
+            .map(|(_, frame)| frame)
+            .expect("StreamPlayer::current before advance")
+    }
+
+    /// Clip frame shown at `position`; until the end is found the first
+    /// pass plays straight through.
+    fn index(&self, position: usize) -> usize {
+        match self.len {
+            None => position,
+            Some(len) if self.playback == Playback::PingPong && len > 2 => {
+                let p = position % (2 * len - 2);
+                if p < len {
+                    p
+                } else {
+                    2 * len - 2 - p
+                }
+            }
+            Some(len) => position % len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeline_loops_and_ping_pongs() {
+        let delays = [0.1, 0.2, 0.1];
+        let times = [0.0, 0.15, 0.35, 0.45, 0.55, 0.65, 0.75];
+
+        let looped = Timeline::new(&delays, Playback::Loop);
+        let frames: Vec<_> = times.iter().map(|&t| looped.frame_at(t)).collect();
+        assert_eq!(frames, [0, 1, 2, 0, 1, 1, 2]);
+
+        // 0 1 2 1 | 0 ...: end frames are not shown twice in a row
+        let ping_pong = Timeline::new(&delays, Playback::PingPong);
+        let frames: Vec<_> = times.iter().map(|&t| ping_pong.frame_at(t)).collect();
+        assert_eq!(frames, [0, 1, 2, 1, 1, 0, 1]);
+    }
+
+    /// Frames are their indices; a third of `MAX_FRAME_BYTES` each, so the
+    /// cache holds three.
+    struct Counted {
+        len: usize,
+        next: usize,
+        decoded: usize,
+    }
+
+    impl FrameStream for Counted {
+        type Frame = usize;
+
+        fn seek(&mut self, start: usize) -> Result<(), String> {
+            self.next = start;
+            Ok(())
+        }
+
+        fn next_frame(&mut self) -> Result<Option<usize>, String> {
+            if self.next >= self.len {
+                return Ok(None);
+            }
+            self.next += 1;
+            self.decoded += 1;
+            Ok(Some(self.next - 1))
+        }
+
+        fn frame_bytes(_: &usize) -> usize {
+            MAX_FRAME_BYTES / 3
+        }
+    }
+
+    fn play(len: usize, playback: Playback, positions: usize) -> (Vec<usize>, usize) {
+        let mut player = StreamPlayer::new(Counted { len, next: 0, decoded: 0 }, playback);
+        let frames = (0..positions)
+            .map(|p| {
+                player.advance(p).unwrap();
+                *player.current()
+            })
+            .collect();
+        (frames, player.stream.decoded)
+    }
+
+    #[test]
+    fn stream_player_decodes_a_clip_that_fits_once() {
+        let (frames, decoded) = play(3, Playback::Loop, 7);
+        assert_eq!(frames, [0, 1, 2, 0, 1, 2, 0]);
+        assert_eq!(decoded, 3);
+    }
+
+    #[test]
+    fn stream_player_ping_pongs_a_long_clip_from_a_bounded_cache() {
+        let (frames, decoded) = play(5, Playback::PingPong, 16);
+        assert_eq!(frames, [0, 1, 2, 3, 4, 3, 2, 1, 0, 1, 2, 3, 4, 3, 2, 1]);
+        // 0-4, then 0-1 for the end of the reverse pass, 2-4 again, 0-1
+        assert_eq!(decoded, 12);
+
+        let (frames, decoded) = play(5, Playback::Loop, 10);
+        assert_eq!(frames, [0, 1, 2, 3, 4, 0, 1, 2, 3, 4]);
+        assert_eq!(decoded, 10);
+    }
+
+    #[test]
+    fn stream_player_rejects_an_empty_clip() {
+        let mut player = StreamPlayer::new(Counted { len: 0, next: 0, decoded: 0 }, Playback::Loop);
+        assert!(player.advance(0).unwrap_err().contains("no frames"));
+    }
+
+    #[test]
+    fn frame_budget_is_shared_by_decode_and_fit() {
+        let mut budget = FrameBudget::default();
+        budget.take(MAX_FRAME_BYTES / 2).unwrap();
+        // Decoded frames and their fitted copies count together
+        budget.take(MAX_FRAME_BYTES / 2).unwrap();
+        assert!(budget.take(1).is_err());
+        budget.release(MAX_FRAME_BYTES / 2 + 1);
+        budget.take(1).unwrap();
+    }
+}
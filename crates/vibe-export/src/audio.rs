@@ -0,0 +1,131 @@
+//! Audio decode (Symphonia) and per-track tempo analysis.
+
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::probe::Hint;
+use vibe_engine::{estimate_tempo, TempoSection};
+
+/// Decoded audio ready for visualization and export.
+pub struct DecodedAudio {
+    /// Left and right channels; mono sources fill both with the same signal.
+    pub left: Vec<f32>,
+    pub right: Vec<f32>,
+    pub sample_rate: u32,
+    /// Sample index where each track ends (exclusive), in track order.
+    pub track_ends: Vec<usize>,
+}
+
+/// Decode multiple audio files into a single buffer.
+/// All tracks must have the same sample rate (fails fast if mismatch).
+pub fn decode_tracks(paths: &[impl AsRef<Path>]) -> Result<DecodedAudio, String> {
+    let mut left: Vec<f32> = Vec::new();
+    let mut right: Vec<f32> = Vec::new();
+    let mut expected_rate: Option<u32> = None;
+    let mut track_ends: Vec<usize> = Vec::with_capacity(paths.len());
+
+    for (i, path) in paths.iter().enumerate() {
+        let path = path.as_ref();
+        let track_num = i + 1;
+
+        let src = std::fs::File::open(path)
+            .map_err(|e| format!("Failed to open track {}: {}", track_num, e))?;
+        let mss = MediaSourceStream::new(Box::new(src), Default::default());
+
+        let probed = symphonia::default::get_probe()
+            .format(&Hint::new(), mss, &Default::default(), &Default::default())
+            .map_err(|e| format!("Failed to probe track {}: {}", track_num, e))?;
+
+        let mut format = probed.format;
+        let track = format
+            .default_track()
+            .ok_or_else(|| format!("No audio stream in track {}", track_num))?;
+        let track_id = track.id;
+
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &Default::default())
+            .map_err(|e| format!("Failed to create decoder for track {}: {}", track_num, e))?;
+
+        while let Ok(packet) = format.next_packet() {
+            if packet.track_id() != track_id {
+                continue;
+            }
+
+            let decoded = decoder
+                .decode(&packet)
+                .map_err(|e| format!("Decode error in track {}: {}", track_num, e))?;
+
+            let spec = *decoded.spec();
+
+            // Validate sample rate consistency (fail-fast)
+            match expected_rate {
+                None => expected_rate = Some(spec.rate),
+                Some(rate) if rate != spec.rate => {
+                    return Err(format!(
+                        "Sample rate mismatch: track {} is {}Hz, but previous tracks are {}Hz. \
+                         All tracks must have the same sample rate.",
+                        track_num, spec.rate, rate
+                    ));
+                }
+                _ => {}
+            }
+
+            let mut sample_buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            sample_buf.copy_interleaved_ref(decoded);
+
+            // Front pair; mono is duplicated so both sides match the preview
+            let channels = spec.channels.count();
+            for frame in sample_buf.samples().chunks(channels) {
+                left.push(frame[0]);
+                right.push(frame[frame.len().min(2) - 1]);
+            }
+        }
+
+        track_ends.push(left.len());
+    }
+
+    let sample_rate = expected_rate.ok_or("No audio data decoded")?;
+    if left.is_empty() {
+        return Err("Audio decode produced no samples".into());
+    }
+
+    Ok(DecodedAudio {
+        left,
+        right,
+        sample_rate,
+        track_ends,
+    })
+}
+
+/// Fit a beat grid to each track, so tempo-locked effects stay in phase for
+/// the whole export. Tracks without a clear tempo get no section and fall
+/// back to reactive beat detection.
+pub fn analyze_tempo(audio: &DecodedAudio) -> Vec<TempoSection> {
+    let rate = audio.sample_rate as f64;
+    let mut start = 0;
+    let mut sections = Vec::new();
+
+    for (i, &end) in audio.track_ends.iter().enumerate() {
+        let track: Vec<f32> = audio.left[start..end]
+            .iter()
+            .zip(&audio.right[start..end])
+            .map(|(l, r)| (l + r) / 2.0)
+            .collect();
+        let start_secs = start as f64 / rate;
+        match estimate_tempo(&track, audio.sample_rate) {
+            Some(tempo) => {
+                log::info!("Track {}: {:.2} BPM", i + 1, tempo.bpm);
+                sections.push(TempoSection {
+                    start: start_secs,
+                    end: end as f64 / rate,
+                    bpm: tempo.bpm,
+                    first_beat: start_secs + tempo.first_beat,
+                });
+            }
+            None => log::info!("Track {}: no stable tempo", i + 1),
+        }
+        start = end;
+    }
+
+    sections
+}
@@ -0,0 +1,282 @@
+//! Headless renderer: the desktop export pipeline from the command line.
+//!
+//! ```text
+//! vibe-machine-cli [options] <audio>... -o <output.mp4>
+//! vibe-machine-cli [options] --project <session.vibe> -o <output.mp4>
+//! ```
+
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+
+use vibe_engine::VibeSettings;
+use vibe_export::{
+    render_video, BackgroundFit, BackgroundStyle, CancelToken, Fill, FitMode, KenBurns, Playback,
+    Project, ProjectBackground, ReactiveBackground, RenderJob,
+};
+
+const USAGE: &str = "\
+Usage: vibe-machine-cli [options] <audio>... -o <output.mp4>
+       vibe-machine-cli [options] --project <session.vibe> -o <output.mp4>
+
+Renders the audio files (concatenated in order), or a saved project, to an
+MP4, exactly as the desktop app's export does.
+
+Options:
+  -o, --output <path>     Output MP4 (required)
+  -p, --project <path>    Render a .vibe project; other options override it
+  -i, --image <path>      Background image, GIF/APNG or short video clip
+  -s, --settings <path>   Engine settings JSON (snake_case VibeSettings);
+                          fields given replace the app defaults (or the
+                          project's settings)
+      --size <WxH>        Frame size (default 1920x1080)
+      --fps <n>           Frame rate (default 30)
+      --progress          Draw the progress bar
+      --fit <mode>        How the background fills the frame: cover (default),
+                          contain, blurred-fill, tile or stretch
+      --focus <x,y>       Point of the image kept in view by the cover crop, or
+                          tiling starts from, 0..1 from the top-left
+                          (default 0.5,0.5)
+      --fill <colour>     Letterbox / no-image colour: #rrggbb, or
+                          #rrggbb:#rrggbb for a top-to-bottom gradient
+      --ken-burns         Slow pan/zoom of the background image
+      --ken-burns-seed <n>
+                          Pan/zoom directions (implies --ken-burns)
+      --blur              Blurred, darkened background (the app's toggle)
+      --reactive          Background pulses, pumps and shakes with the music
+      --ping-pong         Play an animated background forward then backward
+                          (default: loop)
+      --ffmpeg <path>     FFmpeg binary (default: ffmpeg on PATH)
+  -h, --help              Show this help";
+
+/// App defaults for the fields `VibeSettings` requires.
+const DEFAULT_SETTINGS: &str = r##"{
+    "visualizer_mode": "Bars",
+    "visualizer_color": "#ffb703",
+    "visualizer_intensity": 1.0
+}"##;
+
+struct Args {
+    job: RenderJob,
+    ffmpeg: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    // Status changes get their own line; render progress rewrites one line
+    let mut last_status = String::new();
+    let result = render_video(&args.job, Command::new(&args.ffmpeg), &CancelToken::default(), |p| {
+        if p.status != last_status {
+            if p.progress > 0.0 {
+                eprintln!();
+            }
+            eprintln!("{}", p.status);
+            last_status = p.status.clone();
+        } else {
+            eprint!("\r{:5.1}%", p.progress * 100.0);
+        }
+    });
+
+    match result {
+        Ok(()) => {
+            println!("{}", args.job.output_path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("\nerror: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// `Ok(None)` when help was requested.
+fn parse_args(mut argv: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut audio_paths = Vec::new();
+    let mut output_path = None;
+    let mut project_path = None;
+    let mut image_path = None;
+    let mut settings_path = None;
+    let mut size = None;
+    let mut fps = None;
+    let mut show_progress = false;
+    let mut fit_mode = None;
+    let mut focus = None;
+    let mut fill = None;
+    let mut ken_burns = false;
+    let mut ken_burns_seed = None;
+    let mut blur = false;
+    let mut reactive = false;
+    let mut ping_pong = false;
+    let mut ffmpeg = PathBuf::from("ffmpeg");
+
+    while let Some(arg) = argv.next() {
+        let mut value = || argv.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output" => output_path = Some(PathBuf::from(value()?)),
+            "-p" | "--project" => project_path = Some(PathBuf::from(value()?)),
+            "-i" | "--image" => image_path = Some(PathBuf::from(value()?)),
+            "-s" | "--settings" => settings_path = Some(PathBuf::from(value()?)),
+            "--size" => size = Some(parse_size(&value()?)?),
+            "--fps" => {
+                let v = value()?;
+                fps = Some(v.parse().ok().filter(|&n| n > 0).ok_or(format!("invalid fps: {}", v))?);
+            }
+            "--progress" => show_progress = true,
+            "--fit" => fit_mode = Some(parse_fit_mode(&value()?)?),
+            "--focus" => focus = Some(parse_focus(&value()?)?),
+            "--fill" => fill = Some(parse_fill(&value()?)),
+            "--blur" => blur = true,
+            "--reactive" => reactive = true,
+            "--ping-pong" => ping_pong = true,
+            "--ken-burns" => ken_burns = true,
+            "--ken-burns-seed" => {
+                let v = value()?;
+                ken_burns_seed = Some(v.parse().map_err(|_| format!("invalid seed: {}", v))?);
+            }
+            "--ffmpeg" => ffmpeg = PathBuf::from(value()?),
+            flag if flag.starts_with('-') && flag != "-" => {
+                return Err(format!("unknown option: {}", flag))
+            }
+            path => audio_paths.push(PathBuf::from(path)),
+        }
+    }
+
+    let output_path = output_path.ok_or("--output is required")?;
+    // Effect settings a project keeps while the effect is off
+    let mut stored: Option<ProjectBackground> = None;
+    let mut job = match project_path {
+        Some(_) if !audio_paths.is_empty() => {
+            return Err("give either audio files or --project, not both".into())
+        }
+        Some(path) => {
+            let project = Project::load(&path)?;
+            let missing = project.missing_files();
+            if !missing.is_empty() {
+                let list: Vec<_> = missing.iter().map(|p| p.display().to_string()).collect();
+                return Err(format!("project files not found: {}", list.join(", ")));
+            }
+            stored = Some(project.background.clone());
+            RenderJob::from_project(project, output_path)
+        }
+        None if audio_paths.is_empty() => return Err("no audio files given".into()),
+        None => RenderJob {
+            audio_paths,
+            image_path: None,
+            output_path,
+            settings: load_settings(DEFAULT_SETTINGS.parse().unwrap(), None)?,
+            fps: 30,
+            width: 1920,
+            height: 1080,
+            show_progress: false,
+            background_fit: BackgroundFit::default(),
+            ken_burns: None,
+            background_style: BackgroundStyle::default(),
+            reactive_background: ReactiveBackground::default(),
+            background_playback: Playback::Loop,
+            track_overlays: Vec::new(),
+            track_boundaries: Vec::new(),
+        },
+    };
+
+    if let Some(path) = settings_path {
+        let base = serde_json::to_value(&job.settings).map_err(|e| e.to_string())?;
+        job.settings = load_settings(base, Some(path))?;
+    }
+    if image_path.is_some() {
+        job.image_path = image_path;
+    }
+    if let Some((width, height)) = size {
+        job.width = width;
+        job.height = height;
+    }
+    job.fps = fps.unwrap_or(job.fps);
+    job.show_progress |= show_progress;
+    if let Some(mode) = fit_mode {
+        job.background_fit.mode = mode;
+    }
+    if let Some(focus) = focus {
+        job.background_fit.focus = focus;
+    }
+    if let Some(fill) = fill {
+        job.background_fit.fill = fill;
+    }
+    if blur {
+        job.background_style = stored.as_ref().map_or_else(BackgroundStyle::blurred, |b| b.style.clone());
+    }
+    if reactive {
+        job.reactive_background =
+            stored.as_ref().map_or_else(ReactiveBackground::pulse, |b| b.reactive_strengths.clone());
+    }
+    if ping_pong {
+        job.background_playback = Playback::PingPong;
+    }
+    if ken_burns || ken_burns_seed.is_some() {
+        // Keeps a project's speed and zoom range, even with Ken Burns off
+        let motion = job
+            .ken_burns
+            .take()
+            .or_else(|| stored.as_ref().map(|b| b.ken_burns_motion.clone()))
+            .unwrap_or_default();
+        let seed = ken_burns_seed.unwrap_or(motion.seed);
+        job.ken_burns = Some(KenBurns { seed, ..motion });
+    }
+
+    Ok(Some(Args { job, ffmpeg }))
+}
+
+fn parse_size(v: &str) -> Result<(i32, i32), String> {
+    let invalid = || format!("invalid size (expected WxH): {}", v);
+    let (w, h) = v.split_once(['x', 'X']).ok_or_else(invalid)?;
+    match (w.parse(), h.parse()) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_fit_mode(v: &str) -> Result<FitMode, String> {
+    serde_json::from_value(serde_json::Value::String(v.replace('-', "_")))
+        .map_err(|_| format!("invalid fit mode: {}", v))
+}
+
+fn parse_focus(v: &str) -> Result<(f32, f32), String> {
+    let invalid = || format!("invalid focus (expected x,y in 0..1): {}", v);
+    let (x, y) = v.split_once(',').ok_or_else(invalid)?;
+    match (x.trim().parse::<f32>(), y.trim().parse::<f32>()) {
+        (Ok(x), Ok(y)) if (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) => Ok((x, y)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Colours are checked when the frame composer is built.
+fn parse_fill(v: &str) -> Fill {
+    match v.split_once(':') {
+        Some((top, bottom)) => Fill::Gradient(top.into(), bottom.into()),
+        None => Fill::Solid(v.into()),
+    }
+}
+
+/// Settings file fields over `base`.
+fn load_settings(mut base: serde_json::Value, path: Option<PathBuf>) -> Result<VibeSettings, String> {
+    if let Some(path) = path {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let overrides: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid settings JSON in {}: {}", path.display(), e))?;
+        let overrides = overrides
+            .as_object()
+            .ok_or_else(|| format!("{}: settings must be a JSON object", path.display()))?;
+        base.as_object_mut().unwrap().extend(overrides.clone());
+    }
+    serde_json::from_value(base).map_err(|e| format!("Invalid settings: {}", e))
+}
@@ -0,0 +1,412 @@
+//! FFmpeg processes: the encoder (raw RGBA frames on stdin, audio from a
+//! concat list) and the decoder for video backgrounds.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::thread::JoinHandle;
+use image::RgbaImage;
+use tempfile::{NamedTempFile, TempPath};
+
+
+/// Create FFmpeg concat list file with proper path escaping.
+/// Returns TempPath that keeps the file alive until dropped.
+fn create_concat_file(paths: &[impl AsRef<Path>]) -> Result<TempPath, String> {
+    let file = NamedTempFile::new()
+        .map_err(|e| format!("Failed to create concat file: {}", e))?;
+
+    {
+        let mut writer = std::io::BufWriter::new(&file);
+        for path in paths {
+            let path_str = path.as_ref().to_str().ok_or("Invalid path encoding")?;
+            // Escape for FFmpeg concat format:
+            // 1. Backslashes (Windows paths) - escape first
+            // 2. Single quotes - use shell-style escaping
+            let escaped = path_str
+                .replace('\\', "\\\\")
+                .replace('\'', "'\\''");
+            writeln!(writer, "file '{}'", escaped).map_err(|e| e.to_string())?;
+        }
+        writer.flush().map_err(|e| e.to_string())?;
+    }
+
+    // Convert to TempPath - file persists until TempPath is dropped
+    Ok(file.into_temp_path())
+}
+
+/// A running FFmpeg encode. Dropping without `finish` kills the process.
+pub struct Encoder {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    /// Collects stderr so a failed encode can report why.
+    stderr: Option<JoinHandle<String>>,
+    output: PathBuf,
+    /// Keeps the concat list alive until FFmpeg exits.
+    _concat: TempPath,
+}
+
+impl Encoder {
+    /// Spawn `ffmpeg` (a command for the binary, without arguments) to
+    /// encode `width`x`height` RGBA frames at `fps` with the concatenated
+    /// `audio_paths` into `output` (H.264 + AAC MP4).
+    pub fn spawn(
+        mut ffmpeg: Command,
+        width: i32,
+        height: i32,
+        fps: u32,
+        audio_paths: &[impl AsRef<Path>],
+        output: &Path,
+    ) -> Result<Self, String> {
+        let concat = create_concat_file(audio_paths)?;
+        let concat_path = concat.to_str().ok_or("Invalid concat file path encoding")?;
+        let output_str = output.to_str().ok_or("Invalid output path encoding")?;
+
+        let mut child = ffmpeg
+            .args([
+                "-hide_banner",
+                "-loglevel",
+                "error",
+                "-y",
+                "-f",
+                "rawvideo",
+                "-pixel_format",
+                "rgba",
+                "-video_size",
+                &format!("{}x{}", width, height),
+                "-framerate",
+                &fps.to_string(),
+                "-i",
+                "-", // stdin (video frames)
+                "-f",
+                "concat",
+                "-safe",
+                "0",
+                "-i",
+                concat_path, // audio from concat list
+                "-map",
+                "0:v",
+                "-map",
+                "1:a",
+                "-c:v",
+                "libx264",
+                "-pix_fmt",
+                "yuv420p",
+                "-c:a",
+                "aac",
+                "-shortest",
+                output_str,
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start ffmpeg: {}", e))?;
+
+        let stdin = child.stdin.take();
+        let stderr = child.stderr.take().map(|mut pipe| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                let _ = pipe.read_to_string(&mut text);
+                text
+            })
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            stderr,
+            output: output.to_path_buf(),
+            _concat: concat,
+        })
+    }
+
+    pub fn write_frame(&mut self, frame: &[u8]) -> Result<(), String> {
+        let stdin = self.stdin.as_mut().ok_or("ffmpeg stdin already closed")?;
+        stdin.write_all(frame).map_err(|e| e.to_string())
+    }
+
+    /// Close stdin and wait for FFmpeg; errors carry its stderr.
+    pub fn finish(mut self) -> Result<(), String> {
+        drop(self.stdin.take());
+        let status = self.child.wait().map_err(|e| e.to_string())?;
+        let stderr = self
+            .stderr
+            .take()
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default();
+
+        if !status.success() {
+            log::error!("ffmpeg exited with code {:?}: {}", status.code(), stderr.trim());
+            return Err(format!(
+                "ffmpeg exited with code {:?}: {}",
+                status.code(),
+                stderr.trim()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Kill FFmpeg and delete the partial output.
+    pub fn abort(mut self) {
+        drop(self.stdin.take());
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        // Only after FFmpeg exits: Windows won't delete an open file
+        if let Err(e) = std::fs::remove_file(&self.output) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove partial output: {}", e);
+            }
+        }
+    }
+}
+
+impl Drop for Encoder {
+    fn drop(&mut self) {
+        // Still running only if `finish` wasn't reached (render error)
+        if self.stdin.is_some() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// A copy of `cmd` (program, arguments, environment, working directory), so
+/// one FFmpeg command can be run more than once.
+fn command_like(cmd: &Command) -> Command {
+    let mut copy = Command::new(cmd.get_program());
+    copy.args(cmd.get_args());
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => copy.env(key, value),
+            None => copy.env_remove(key),
+        };
+    }
+    if let Some(dir) = cmd.get_current_dir() {
+        copy.current_dir(dir);
+    }
+    copy
+}
+
+/// Decodes video backgrounds with the export's FFmpeg at its frame rate.
+pub struct VideoDecoder<'a> {
+    pub ffmpeg: &'a Command,
+    pub fps: u32,
+}
+
+impl VideoDecoder<'_> {
+    /// The video at `path`, scaled to `size`: FFmpeg `scale` width and
+    /// height expressions of the source `iw`x`ih`. Nothing is decoded until
+    /// the stream is seeked.
+    pub fn open(&self, path: &Path, size: [String; 2]) -> Result<VideoStream, String> {
+        let path = path.to_str().ok_or("Invalid image path encoding")?;
+        let [w, h] = size;
+        let fps = self.fps.max(1);
+        Ok(VideoStream {
+            ffmpeg: command_like(self.ffmpeg),
+            path: path.to_string(),
+            filter: format!("scale=w='{}':h='{}':flags=lanczos,fps={}", w, h, fps),
+            fps,
+            decoding: None,
+        })
+    }
+}
+
+/// One video's frames, read in order from an FFmpeg run started at any
+/// frame.
+pub struct VideoStream {
+    ffmpeg: Command,
+    path: String,
+    filter: String,
+    fps: u32,
+    decoding: Option<Decoding>,
+}
+
+struct Decoding {
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+    stderr: Option<JoinHandle<String>>,
+}
+
+impl VideoStream {
+    /// Restart decoding at frame `start`.
+    pub fn seek(&mut self, start: usize) -> Result<(), String> {
+        self.stop();
+        let mut cmd = command_like(&self.ffmpeg);
+        cmd.args(["-hide_banner", "-loglevel", "error"]);
+        if start > 0 {
+            // Input seeking decodes from the keyframe before and drops
+            // frames up to the time
+            cmd.args(["-ss", &format!("{:.6}", start as f64 / self.fps as f64)]);
+        }
+        let mut child = cmd
+            .args(["-i", &self.path, "-an", "-vf", &self.filter])
+            .args(["-f", "image2pipe", "-c:v", "ppm", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start ffmpeg: {}", e))?;
+
+        let stderr = child.stderr.take().map(|mut pipe| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                let _ = pipe.read_to_string(&mut text);
+                text
+            })
+        });
+        let stdout = child.stdout.take().map(BufReader::new);
+        let Some(stdout) = stdout else {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("ffmpeg stdout unavailable".into());
+        };
+        self.decoding = Some(Decoding { child, stdout, stderr });
+        Ok(())
+    }
+
+    /// Next frame; `None` at the end of the video or before any `seek`.
+    pub fn next_frame(&mut self) -> Result<Option<RgbaImage>, String> {
+        let Some(decoding) = self.decoding.as_mut() else {
+            return Ok(None);
+        };
+        match read_ppm(&mut decoding.stdout) {
+            Ok(Some(frame)) => Ok(Some(frame)),
+            Ok(None) => {
+                let Some(mut decoding) = self.decoding.take() else {
+                    return Ok(None);
+                };
+                let status = decoding.child.wait().map_err(|e| e.to_string())?;
+                let stderr = decoding.stderr.and_then(|handle| handle.join().ok()).unwrap_or_default();
+                if !status.success() {
+                    return Err(format!("background video decode failed: {}", stderr.trim()));
+                }
+                Ok(None)
+            }
+            Err(e) => {
+                self.stop();
+                Err(e)
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut decoding) = self.decoding.take() {
+            let _ = decoding.child.kill();
+            let _ = decoding.child.wait();
+        }
+    }
+}
+
+impl Drop for VideoStream {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Next frame of a binary PPM stream (P6, 8-bit); `None` at end of stream.
+fn read_ppm(reader: &mut impl BufRead) -> Result<Option<RgbaImage>, String> {
+    if reader.fill_buf().map_err(|e| e.to_string())?.is_empty() {
+        return Ok(None);
+    }
+    if read_ppm_token(reader)? != "P6" {
+        return Err("video frame read failed: not a PPM frame".into());
+    }
+    let mut fields = [0usize; 3];
+    for field in &mut fields {
+        *field = read_ppm_token(reader)?
+            .parse()
+            .map_err(|_| "video frame read failed: invalid PPM header")?;
+    }
+    let [width, height, max_value] = fields;
+    let size = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(3))
+        .filter(|&n| n > 0 && max_value == 255)
+        .ok_or("video frame read failed: unsupported PPM header")?;
+
+    // The header's last token consumed the single separator before the data
+    let mut rgb = vec![0u8; size];
+    reader
+        .read_exact(&mut rgb)
+        .map_err(|e| format!("video frame read failed: {}", e))?;
+    let rgba = rgb.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect();
+    Ok(RgbaImage::from_raw(width as u32, height as u32, rgba))
+}
+
+/// Whitespace-separated header token, skipping `#` comments.
+fn read_ppm_token(reader: &mut impl BufRead) -> Result<String, String> {
+    let mut token = String::new();
+    let mut byte = [0u8];
+    loop {
+        reader
+            .read_exact(&mut byte)
+            .map_err(|e| format!("video frame read failed: {}", e))?;
+        match byte[0] {
+            b'#' if token.is_empty() => {
+                reader.read_until(b'\n', &mut Vec::new()).map_err(|e| e.to_string())?;
+            }
+            b if b.is_ascii_whitespace() => {
+                if !token.is_empty() {
+                    return Ok(token);
+                }
+            }
+            b => token.push(b as char),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn video_stream_reads_ppm_frames_from_a_seek() {
+        // Stand-in decoder: two 2x1 frames (red/green, then blue/white),
+        // or just the second when seeked to it
+        let mut ffmpeg = Command::new("sh");
+        ffmpeg.args([
+            "-c",
+            r#"case " $* " in
+                *" -ss 0.033333 "*) printf 'P6 2 1 255 \000\000\377\377\377\377' ;;
+                *) printf 'P6\n2 1\n255\n\377\000\000\000\377\000'; printf 'P6 2 1 255 \000\000\377\377\377\377' ;;
+            esac"#,
+            "sh",
+        ]);
+        let decoder = VideoDecoder { ffmpeg: &ffmpeg, fps: 30 };
+        let mut stream = decoder.open(Path::new("/clip.mp4"), ["2".into(), "1".into()]).unwrap();
+        let read = |stream: &mut VideoStream| {
+            std::iter::from_fn(|| stream.next_frame().unwrap())
+                .map(|frame| frame.into_raw())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(read(&mut stream).is_empty());
+        stream.seek(0).unwrap();
+        assert_eq!(
+            read(&mut stream),
+            [
+                vec![255, 0, 0, 255, 0, 255, 0, 255],
+                vec![0, 0, 255, 255, 255, 255, 255, 255]
+            ]
+        );
+        stream.seek(1).unwrap();
+        assert_eq!(read(&mut stream), [vec![0, 0, 255, 255, 255, 255, 255, 255]]);
+    }
+
+    #[test]
+    fn video_stream_seek_stops_the_previous_decode() {
+        // Stand-in decoder: one frame, then a stall a re-seek must not wait out
+        let mut ffmpeg = Command::new("sh");
+        ffmpeg.args(["-c", r"printf 'P6 1 1 255 ÿÿÿ'; exec sleep 30", "sh"]);
+        let decoder = VideoDecoder { ffmpeg: &ffmpeg, fps: 30 };
+        let mut stream = decoder.open(Path::new("/clip.mp4"), ["1".into(), "1".into()]).unwrap();
+
+        let started = std::time::Instant::now();
+        stream.seek(0).unwrap();
+        assert!(stream.next_frame().unwrap().is_some());
+        stream.seek(0).unwrap();
+        assert!(stream.next_frame().unwrap().is_some());
+        drop(stream);
+        assert!(started.elapsed().as_secs() < 10);
+    }
+}
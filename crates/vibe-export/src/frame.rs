@@ -0,0 +1,1234 @@
+use std::path::Path;
+
+use base64::Engine;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+use crate::animation::{self, FrameBudget, FrameStream, Playback, Source, StreamPlayer, Timeline};
+use crate::ffmpeg::{VideoDecoder, VideoStream};
+
+struct Rect {
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+}
+
+pub struct OverlayConfig {
+    pub show_progress: bool,
+    pub accent_rgb: (u8, u8, u8),
+}
+
+/// Slow pan/zoom of the background image. Same motion as the preview
+/// (`utils/kenBurns.ts`), parameterised by video time.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct KenBurns {
+    /// 1.0 = the preview's pace (one zoom cycle every ~2 minutes).
+    pub speed: f32,
+    /// Zoom range; kept at or above 1.0 so the image always covers the frame.
+    pub zoom_min: f32,
+    pub zoom_max: f32,
+    /// Pan distance as a fraction of the frame's shorter side.
+    pub pan: f32,
+    /// Picks the directions: bit 0 flips horizontal pan, bit 1 vertical,
+    /// bit 2 starts by zooming out.
+    pub seed: u32,
+}
+
+impl Default for KenBurns {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            zoom_min: 1.03,
+            zoom_max: 1.07,
+            pan: 0.014,
+            seed: 0,
+        }
+    }
+}
+
+impl KenBurns {
+    /// (scale, x offset, y offset) at `time_secs`, offsets in pixels for a
+    /// frame whose shorter side is `short_side`.
+    fn transform(&self, time_secs: f64, short_side: f32) -> (f32, f32, f32) {
+        // Wrapped at the common period of the three terms (20π) so long
+        // videos keep f32 precision
+        let t = ((time_secs * self.speed as f64 / 20.0) % (10.0 * std::f64::consts::TAU)) as f32;
+        let flip = |bit: u32| if (self.seed >> bit) & 1 == 1 { -1.0 } else { 1.0 };
+
+        let (lo, hi) = self.zoom_range();
+        let scale = (lo + hi) / 2.0 + flip(2) * t.sin() * (hi - lo) / 2.0;
+        let pan = self.pan * short_side;
+        (scale, flip(0) * (t * 0.5).cos() * pan, flip(1) * (t * 0.3).sin() * pan)
+    }
+
+    fn zoom_range(&self) -> (f32, f32) {
+        let lo = self.zoom_min.max(1.0);
+        (lo, self.zoom_max.max(lo))
+    }
+}
+
+/// Look of the background image, applied once when the composer is built.
+/// The default leaves the image as is.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BackgroundStyle {
+    /// Gaussian blur (standard deviation, pixels) at 1080p; scaled with the
+    /// frame's shorter side.
+    pub blur: f32,
+    /// 0 = unchanged, 1 = black.
+    pub dim: f32,
+    /// 0 = unchanged, 1 = greyscale.
+    pub desaturate: f32,
+}
+
+impl BackgroundStyle {
+    /// The app's blurred background toggle (`utils/backgroundStyle.ts`).
+    pub fn blurred() -> Self {
+        Self { blur: 12.0, dim: 0.35, desaturate: 0.2 }
+    }
+
+    /// Apply to a cover-fit `image`; `px_per_1080p` converts the blur
+    /// radius to this image's pixels. Same order as the preview's canvas
+    /// filter: blur, saturate, brightness.
+    fn apply(&self, image: RgbaImage, px_per_1080p: f32) -> RgbaImage {
+        let sigma = self.blur * px_per_1080p;
+        let mut image = if sigma >= 0.5 { image::imageops::fast_blur(&image, sigma) } else { image };
+
+        let saturation = 1.0 - self.desaturate.clamp(0.0, 1.0);
+        let brightness = 1.0 - self.dim.clamp(0.0, 1.0);
+        if saturation < 1.0 || brightness < 1.0 {
+            for pixel in image.pixels_mut() {
+                let [r, g, b, _] = pixel.0.map(|c| c as f32);
+                let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+                for (c, v) in pixel.0.iter_mut().zip([r, g, b]) {
+                    *c = ((luma + (v - luma) * saturation) * brightness).round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+        image
+    }
+}
+
+/// Background treatments driven by the music. Strengths are 0..1 (0 = off),
+/// each scaling a fixed maximum effect.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ReactiveBackground {
+    /// Zoom in with the bass.
+    pub zoom_pulse: f32,
+    /// Darken between bass hits, so the image pumps with the low end.
+    pub brightness_pump: f32,
+    /// Split red and blue outward on beats.
+    pub chromatic: f32,
+    /// Jolt the image on beats.
+    pub shake: f32,
+}
+
+/// Largest zoom added by `zoom_pulse` at full bass.
+const ZOOM_PULSE_MAX: f32 = 0.06;
+/// Largest darkening by `brightness_pump` with no bass.
+const PUMP_MAX: f32 = 0.35;
+/// Red/blue scale offset by `chromatic` on a full-strength beat.
+const ABERRATION_MAX: f32 = 0.008;
+/// Largest shake offset, as a fraction of the frame's shorter side.
+const SHAKE_MAX: f32 = 0.012;
+
+impl ReactiveBackground {
+    /// The app's reactive background toggle (`utils/reactiveBackground.ts`).
+    pub fn pulse() -> Self {
+        Self { zoom_pulse: 0.5, brightness_pump: 0.5, chromatic: 0.5, shake: 0.3 }
+    }
+
+    fn is_active(&self) -> bool {
+        self.zoom_pulse > 0.0 || self.brightness_pump > 0.0 || self.chromatic > 0.0 || self.shake > 0.0
+    }
+
+    /// Constant zoom leaving room to shake without showing the image edge.
+    fn overscan(&self) -> f32 {
+        1.0 + 2.0 * SHAKE_MAX * self.shake.clamp(0.0, 1.0)
+    }
+
+    /// Largest zoom this config reaches, for sizing the source image.
+    fn max_zoom(&self) -> f32 {
+        self.overscan() * (1.0 + ZOOM_PULSE_MAX * self.zoom_pulse.clamp(0.0, 1.0))
+    }
+}
+
+/// How the background image fills a frame of a different shape.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitMode {
+    /// Scaled to cover the frame, cropped around the focus point.
+    #[default]
+    Cover,
+    /// The whole image, letterboxed with the fill.
+    Contain,
+    /// The whole image over a blurred, darkened cover-fit copy of itself.
+    BlurredFill,
+    /// Repeated out from one tile placed at the focus point; tiles are half
+    /// the frame's shorter side, their long side at most the frame's.
+    Tile,
+    /// Scaled to the frame, ignoring the aspect ratio.
+    Stretch,
+}
+
+/// Behind a letterboxed (or transparent) image, and the whole frame when
+/// there is no image. Colours are `#rrggbb`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fill {
+    Solid(String),
+    /// Top to bottom.
+    Gradient(String, String),
+}
+
+impl Default for Fill {
+    fn default() -> Self {
+        Fill::Solid("#030304".into())
+    }
+}
+
+impl Fill {
+    /// A `width`x`height` image of the fill; errors on a malformed colour.
+    fn paint(&self, width: u32, height: u32) -> Result<RgbaImage, String> {
+        let (top, bottom) = match self {
+            Fill::Solid(colour) => (parse_colour(colour)?, parse_colour(colour)?),
+            Fill::Gradient(top, bottom) => (parse_colour(top)?, parse_colour(bottom)?),
+        };
+        let last = height.saturating_sub(1).max(1) as f32;
+        Ok(RgbaImage::from_fn(width, height, |_, y| {
+            let t = y as f32 / last;
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            Rgba([mix(top[0], bottom[0]), mix(top[1], bottom[1]), mix(top[2], bottom[2]), 255])
+        }))
+    }
+}
+
+fn parse_colour(hex: &str) -> Result<[u8; 3], String> {
+    let invalid = || format!("invalid fill colour (expected #rrggbb): {}", hex);
+    let h = hex.strip_prefix('#').unwrap_or(hex);
+    if h.len() != 6 || !h.is_ascii() {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&h[i..i + 2], 16).map_err(|_| invalid());
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Where the background image sits in the frame. The default is a centred
+/// cover-fit over the app's near-black.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BackgroundFit {
+    pub mode: FitMode,
+    /// Point of the image kept in view by the cover crop, and where tiling
+    /// starts from, 0..1 from the top-left corner. Unused by other modes.
+    pub focus: (f32, f32),
+    pub fill: Fill,
+}
+
+impl Default for BackgroundFit {
+    fn default() -> Self {
+        Self { mode: FitMode::Cover, focus: (0.5, 0.5), fill: Fill::default() }
+    }
+}
+
+impl BackgroundFit {
+    fn focus(&self) -> (f32, f32) {
+        (self.focus.0.clamp(0.0, 1.0), self.focus.1.clamp(0.0, 1.0))
+    }
+}
+
+/// Backdrop of `FitMode::BlurredFill` (`utils/backgroundFit.ts`), blurred
+/// at 1/`BLURRED_FILL_DOWNSCALE` resolution since it's soft anyway.
+const BLURRED_FILL: BackgroundStyle = BackgroundStyle { blur: 40.0, dim: 0.45, desaturate: 0.0 };
+const BLURRED_FILL_DOWNSCALE: u32 = 4;
+
+/// How the background image is drawn. The default is a still cover-fit.
+#[derive(Clone, Debug, Default)]
+pub struct BackgroundConfig {
+    pub fit: BackgroundFit,
+    pub ken_burns: Option<KenBurns>,
+    pub style: BackgroundStyle,
+    pub reactive: ReactiveBackground,
+    /// Repeat mode of an animated or video background.
+    pub playback: Playback,
+}
+
+/// Per-frame inputs to `compose_into`.
+pub struct FrameContext {
+    pub time_secs: f64,
+    pub index: usize,
+    pub total: usize,
+    /// Track whose text overlay is shown.
+    pub track: usize,
+    /// Bass level 0..1 (`VibeEngine::band_energy`).
+    pub bass: f32,
+    /// Beat pulse 0..1 (`VibeEngine::beat_strength`).
+    pub beat: f32,
+}
+
+enum Background {
+    /// Pre-composed frame (RGBA), copied as is.
+    Static(Vec<u8>),
+    /// Cover-fit image at its most-zoomed resolution, resampled every frame
+    /// for Ken Burns and reactive effects.
+    Resampled {
+        image: RgbaImage,
+        /// Cover-fit size at zoom 1.0, in frame pixels.
+        cover: (f32, f32),
+    },
+}
+
+impl Background {
+    fn bytes(&self) -> usize {
+        match self {
+            Background::Static(pixels) => pixels.len(),
+            Background::Resampled { image, .. } => image.as_raw().len(),
+        }
+    }
+}
+
+/// The background's frames: decoded up front, or streamed for a video.
+enum BackgroundFrames {
+    /// One entry per animation frame, picked by time; a single one for a
+    /// still.
+    Decoded(Vec<Background>, Option<Timeline>),
+    /// One frame per video frame at `fps`, decoded as the export plays.
+    Video { player: Box<StreamPlayer<FittedVideo>>, fps: u32 },
+}
+
+/// Where and how the background is sampled for one frame.
+struct View {
+    scale: f32,
+    tx: f32,
+    ty: f32,
+    /// 1.0 = unchanged.
+    brightness: f32,
+    /// Red drawn at `scale * (1 + aberration)`, blue at `1 - aberration`.
+    aberration: f32,
+}
+
+pub struct FrameComposer {
+    width: usize,
+    height: usize,
+    background: BackgroundFrames,
+    /// Cover-crop focus; centred for the other fit modes.
+    focus: (f32, f32),
+    motion: Option<KenBurns>,
+    reactive: ReactiveBackground,
+    overlay: OverlayConfig,
+    /// Text overlays for each track (indexed by track number)
+    track_overlays: Vec<OverlayImage>,
+}
+
+impl FrameComposer {
+    /// `video` decodes backgrounds that aren't images; without it they fail.
+    pub fn new(
+        width: i32,
+        height: i32,
+        image_path: &str,
+        config: &BackgroundConfig,
+        video: Option<&VideoDecoder>,
+        overlay: OverlayConfig,
+        track_overlay_base64s: &[String],
+    ) -> Result<Self, String> {
+        let width = width.max(1) as usize;
+        let height = height.max(1) as usize;
+
+        // Validate dimensions once; eliminates overflow everywhere else
+        let _ = checked_frame_size(width, height)?;
+
+        let background = load_background(width, height, image_path, config, video)?;
+
+        // Load text overlay for each track (preserve index alignment - empty = transparent)
+        let mut track_overlays = Vec::with_capacity(track_overlay_base64s.len());
+        for (i, base64) in track_overlay_base64s.iter().enumerate() {
+            if base64.is_empty() {
+                track_overlays.push(OverlayImage { pixels: vec![] });
+            } else {
+                track_overlays.push(
+                    load_text_overlay(width, height, base64)
+                        .map_err(|e| format!("track {} overlay: {}", i + 1, e))?,
+                );
+            }
+        }
+
+        Ok(Self {
+            width,
+            height,
+            background,
+            focus: match config.fit.mode {
+                FitMode::Cover => config.fit.focus(),
+                _ => (0.5, 0.5),
+            },
+            motion: config.ken_burns.clone(),
+            reactive: config.reactive.clone(),
+            overlay,
+            track_overlays,
+        })
+    }
+
+    /// Returns the exact buffer size required for `compose_into`.
+    /// Caller allocates once; no size ambiguity.
+    pub fn frame_size(&self) -> usize {
+        self.width * self.height * 4
+    }
+
+    /// Compose a frame; fails only if a video background can't be decoded.
+    /// Panics if buffer sizes mismatch (indicates caller bug).
+    pub fn compose_into(
+        &mut self,
+        engine_pixels: &[u8],
+        frame: &FrameContext,
+        out: &mut [u8],
+    ) -> Result<(), String> {
+        let expected = self.frame_size();
+        assert_eq!(
+            engine_pixels.len(),
+            expected,
+            "engine buffer size mismatch: got {}, expected {}",
+            engine_pixels.len(),
+            expected
+        );
+        assert_eq!(
+            out.len(),
+            expected,
+            "output buffer size mismatch: got {}, expected {}",
+            out.len(),
+            expected
+        );
+
+        if let BackgroundFrames::Video { player, fps } = &mut self.background {
+            player.advance((frame.time_secs * *fps as f64).round() as usize)?;
+        }
+        let background = match &self.background {
+            BackgroundFrames::Decoded(frames, timeline) => {
+                &frames[timeline.as_ref().map_or(0, |t| t.frame_at(frame.time_secs))]
+            }
+            BackgroundFrames::Video { player, .. } => player.current(),
+        };
+        match background {
+            Background::Static(pixels) => out.copy_from_slice(pixels),
+            Background::Resampled { image, cover } => {
+                let view = self.view(*cover, frame);
+                draw_resampled(out, self.width, self.height, image, *cover, &view)
+            }
+        }
+        overlay_rgba(engine_pixels, out);
+
+        // Use the active track's text overlay
+        if let Some(text) = self.track_overlays.get(frame.track) {
+            overlay_rgba(&text.pixels, out);
+        }
+
+        draw_progress(out, self.width, self.height, frame.index, frame.total, &self.overlay);
+        Ok(())
+    }
+
+    /// Ken Burns motion plus this frame's reactive effects. Pan and shake
+    /// are clamped so the image edge never comes into view.
+    fn view(&self, cover: (f32, f32), frame: &FrameContext) -> View {
+        let (w, h) = (self.width as f32, self.height as f32);
+        let short = w.min(h);
+        let (mut scale, mut tx, mut ty) = self
+            .motion
+            .as_ref()
+            .map_or((1.0, 0.0, 0.0), |m| m.transform(frame.time_secs, short));
+
+        let r = &self.reactive;
+        let (bass, beat) = (frame.bass.clamp(0.0, 1.0), frame.beat.clamp(0.0, 1.0));
+        scale *= r.overscan() * (1.0 + ZOOM_PULSE_MAX * r.zoom_pulse.clamp(0.0, 1.0) * bass);
+
+        // Deterministic jitter: same shake for the same frame on every run
+        let t = frame.time_secs as f32;
+        let shake = SHAKE_MAX * r.shake.clamp(0.0, 1.0) * beat * short;
+        tx += shake * ((t * 53.0).sin() + 0.5 * (t * 31.7).cos()) / 1.5;
+        ty += shake * ((t * 47.3).cos() + 0.5 * (t * 29.1).sin()) / 1.5;
+
+        // Off-centre focus: slide the overflow so the focus point stays in view
+        tx += (0.5 - self.focus.0) * (cover.0 * scale - w).max(0.0);
+        ty += (0.5 - self.focus.1) * (cover.1 * scale - h).max(0.0);
+
+        let max_tx = ((cover.0 * scale - w) / 2.0).max(0.0);
+        let max_ty = ((cover.1 * scale - h) / 2.0).max(0.0);
+        View {
+            scale,
+            tx: tx.clamp(-max_tx, max_tx),
+            ty: ty.clamp(-max_ty, max_ty),
+            brightness: 1.0 - PUMP_MAX * r.brightness_pump.clamp(0.0, 1.0) * (1.0 - bass),
+            aberration: ABERRATION_MAX * r.chromatic.clamp(0.0, 1.0) * beat,
+        }
+    }
+}
+
+/// Validates frame dimensions and returns byte size. Fails early on overflow.
+fn checked_frame_size(width: usize, height: usize) -> Result<usize, String> {
+    width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(4))
+        .ok_or_else(|| format!("frame dimensions too large: {}x{}", width, height))
+}
+
+struct OverlayImage {
+    pixels: Vec<u8>,
+}
+
+fn load_text_overlay(width: usize, height: usize, base64_png: &str) -> Result<OverlayImage, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(base64_png.as_bytes())
+        .map_err(|e| format!("overlay base64 decode failed: {}", e))?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|e| format!("overlay image decode failed: {}", e))?
+        .to_rgba8();
+    let overlay = if image.width() as usize == width && image.height() as usize == height {
+        image
+    } else {
+        image::imageops::resize(&image, width as u32, height as u32, FilterType::Triangle)
+    };
+    Ok(OverlayImage {
+        pixels: overlay.into_vec(),
+    })
+}
+
+/// Background frames for `image_path` (the fill when empty): decoded, with
+/// a timeline for an animation, or streamed for a video.
+fn load_background(
+    width: usize,
+    height: usize,
+    image_path: &str,
+    config: &BackgroundConfig,
+    video: Option<&VideoDecoder>,
+) -> Result<BackgroundFrames, String> {
+    // Dimensions already validated by caller; this also checks the colours
+    let fill = config.fit.fill.paint(width as u32, height as u32)?.into_raw();
+    if image_path.is_empty() {
+        return Ok(BackgroundFrames::Decoded(vec![Background::Static(fill)], None));
+    }
+
+    let resampled = config.ken_burns.is_some() || config.reactive.is_active();
+    let zoom = resampled.then(|| {
+        let (_, zoom_max) = config.ken_burns.as_ref().map_or((1.0, 1.0), KenBurns::zoom_range);
+        zoom_max * config.reactive.max_zoom()
+    });
+    let fitter = Fitter { width, height, config: config.clone(), fill, zoom };
+
+    let path = Path::new(image_path);
+    let mut budget = FrameBudget::default();
+    let (frames, delays) = match animation::open(path, &mut budget)? {
+        Source::Still(image) => return Ok(BackgroundFrames::Decoded(vec![fitter.fit(image)?], None)),
+        Source::Animated { frames, delays } => (frames, delays),
+        Source::Video => {
+            let video = video.ok_or("image load failed: unsupported image format")?;
+            let size = video_size(width, height, zoom.unwrap_or(1.0), config);
+            let stream = FittedVideo { stream: video.open(path, size)?, fitter };
+            let mut player = StreamPlayer::new(stream, config.playback);
+            // The first frame now, so a bad clip fails before the export starts
+            player.advance(0)?;
+            return Ok(BackgroundFrames::Video { player: Box::new(player), fps: video.fps.max(1) });
+        }
+    };
+
+    // Each decoded frame is dropped once fitted, handing its bytes back
+    let mut background = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let decoded = frame.as_raw().len();
+        let frame = fitter.fit(DynamicImage::ImageRgba8(frame))?;
+        budget.take(frame.bytes())?;
+        budget.release(decoded);
+        background.push(frame);
+    }
+    Ok(BackgroundFrames::Decoded(background, Some(Timeline::new(&delays, config.playback))))
+}
+
+/// Lays decoded images out on the canvas as `Background`s.
+struct Fitter {
+    width: usize,
+    height: usize,
+    config: BackgroundConfig,
+    /// Painted fill, shown for an empty image.
+    fill: Vec<u8>,
+    /// Most-zoomed scale when frames are resampled for Ken Burns or
+    /// reactive effects.
+    zoom: Option<f32>,
+}
+
+impl Fitter {
+    fn fit(&self, image: DynamicImage) -> Result<Background, String> {
+        let (iw, ih) = image.dimensions();
+        if iw == 0 || ih == 0 {
+            return Ok(Background::Static(self.fill.clone()));
+        }
+        match self.zoom {
+            Some(zoom) => fit_resampled(self.width, self.height, &image, zoom, &self.config),
+            None => {
+                let px_per_1080p = self.width.min(self.height) as f32 / 1080.0;
+                fit_image(&image, self.width as u32, self.height as u32, &self.config, px_per_1080p)
+                    .map(|image| Background::Static(image.into_raw()))
+            }
+        }
+    }
+}
+
+/// Video frames fitted as they're decoded.
+struct FittedVideo {
+    stream: VideoStream,
+    fitter: Fitter,
+}
+
+impl FrameStream for FittedVideo {
+    type Frame = Background;
+
+    fn seek(&mut self, start: usize) -> Result<(), String> {
+        self.stream.seek(start)
+    }
+
+    fn next_frame(&mut self) -> Result<Option<Background>, String> {
+        match self.stream.next_frame()? {
+            Some(frame) => self.fitter.fit(DynamicImage::ImageRgba8(frame)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn frame_bytes(frame: &Background) -> usize {
+        frame.bytes()
+    }
+}
+
+/// FFmpeg size expressions that scale a video frame straight to the size
+/// `fit_image` (or `fit_resampled`) resizes it to, so it is resampled once.
+fn video_size(width: usize, height: usize, zoom: f32, config: &BackgroundConfig) -> [String; 2] {
+    let (cw, ch) = ((width as f32 * zoom).ceil(), (height as f32 * zoom).ceil());
+    let scaled = |scale: String| ["iw", "ih"].map(|side| format!("max(1,round({}*{}))", side, scale));
+    match config.fit.mode {
+        FitMode::Cover => ["iw", "ih"]
+            .map(|side| format!("ceil(max({}/iw,{}/ih)*{}*{})", width, height, side, zoom)),
+        FitMode::Contain | FitMode::BlurredFill => scaled(format!("min({}/iw,{}/ih)", cw, ch)),
+        FitMode::Tile => {
+            scaled(format!("min({}/2/min(iw,ih),{}/max(iw,ih))", cw.min(ch), cw.max(ch)))
+        }
+        FitMode::Stretch => [cw.to_string(), ch.to_string()],
+    }
+}
+
+/// Half the frame's shorter side across the image's shorter side, capped so
+/// the long side of a thin strip stays within the frame.
+fn tile_scale(width: u32, height: u32, iw: u32, ih: u32) -> f32 {
+    let half_short = width.min(height) as f32 / 2.0 / iw.min(ih) as f32;
+    half_short.min(width.max(height) as f32 / iw.max(ih) as f32)
+}
+
+/// `image` resized to `w`x`h`; video frames FFmpeg already scaled to that
+/// size are only converted.
+fn resized(image: &DynamicImage, w: u32, h: u32) -> RgbaImage {
+    if image.dimensions() == (w, h) {
+        image.to_rgba8()
+    } else {
+        image.resize_exact(w, h, FilterType::Lanczos3).to_rgba8()
+    }
+}
+
+/// `image` laid out on a `width`x`height` canvas by the fit mode, then
+/// styled; `px_per_1080p` scales blur radii and tile size to the canvas.
+fn fit_image(
+    image: &DynamicImage,
+    width: u32,
+    height: u32,
+    config: &BackgroundConfig,
+    px_per_1080p: f32,
+) -> Result<RgbaImage, String> {
+    let fit = &config.fit;
+    let (iw, ih) = image.dimensions();
+    let scaled = |scale: f32| {
+        let w = ((iw as f32 * scale).round() as u32).max(1);
+        let h = ((ih as f32 * scale).round() as u32).max(1);
+        resized(image, w, h)
+    };
+    let centred = |canvas: &mut RgbaImage, image: &RgbaImage| {
+        let x = (width as i64 - image.width() as i64) / 2;
+        let y = (height as i64 - image.height() as i64) / 2;
+        image::imageops::overlay(canvas, image, x, y);
+    };
+    let contain = (width as f32 / iw as f32).min(height as f32 / ih as f32);
+
+    let canvas = match fit.mode {
+        FitMode::Cover => {
+            let scale = (width as f32 / iw as f32).max(height as f32 / ih as f32);
+            let new_w = (iw as f32 * scale).ceil() as u32;
+            let new_h = (ih as f32 * scale).ceil() as u32;
+            // Styled before the crop so the blur has real pixels past the edges
+            let styled = config.style.apply(resized(image, new_w, new_h), px_per_1080p);
+            let (fx, fy) = fit.focus();
+            let x = (new_w.saturating_sub(width) as f32 * fx) as u32;
+            let y = (new_h.saturating_sub(height) as f32 * fy) as u32;
+            return Ok(image::imageops::crop_imm(&styled, x, y, width, height).to_image());
+        }
+        FitMode::Contain => {
+            let mut canvas = fit.fill.paint(width, height)?;
+            centred(&mut canvas, &scaled(contain));
+            canvas
+        }
+        FitMode::BlurredFill => {
+            let mut canvas = blurred_backdrop(image, width, height, px_per_1080p);
+            centred(&mut canvas, &scaled(contain));
+            canvas
+        }
+        FitMode::Tile => {
+            let mut canvas = fit.fill.paint(width, height)?;
+            let tile = scaled(tile_scale(width, height, iw, ih));
+            let (tw, th) = (tile.width() as i64, tile.height() as i64);
+            // One tile at the focus (centred by default), the rest repeated
+            // out to the edges
+            let (fx, fy) = fit.focus();
+            let x0 = (((width as i64 - tw) as f32 * fx) as i64).rem_euclid(tw) - tw;
+            let y0 = (((height as i64 - th) as f32 * fy) as i64).rem_euclid(th) - th;
+            for y in (y0..height as i64).step_by(th as usize) {
+                for x in (x0..width as i64).step_by(tw as usize) {
+                    image::imageops::overlay(&mut canvas, &tile, x, y);
+                }
+            }
+            canvas
+        }
+        FitMode::Stretch => {
+            let mut canvas = fit.fill.paint(width, height)?;
+            let stretched = resized(image, width, height);
+            image::imageops::overlay(&mut canvas, &stretched, 0, 0);
+            canvas
+        }
+    };
+    Ok(config.style.apply(canvas, px_per_1080p))
+}
+
+/// Centre-cropped cover fit of `image`, heavily blurred and darkened.
+fn blurred_backdrop(image: &DynamicImage, width: u32, height: u32, px_per_1080p: f32) -> RgbaImage {
+    let (iw, ih) = image.dimensions();
+    let (sw, sh) = ((width / BLURRED_FILL_DOWNSCALE).max(1), (height / BLURRED_FILL_DOWNSCALE).max(1));
+    let scale = (sw as f32 / iw as f32).max(sh as f32 / ih as f32);
+    let (cw, ch) = ((iw as f32 * scale).ceil() as u32, (ih as f32 * scale).ceil() as u32);
+    let small = image.resize_exact(cw, ch, FilterType::Triangle).to_rgba8();
+    let small = image::imageops::crop_imm(&small, (cw - sw) / 2, (ch - sh) / 2, sw, sh).to_image();
+    let small = BLURRED_FILL.apply(small, px_per_1080p / BLURRED_FILL_DOWNSCALE as f32);
+    image::imageops::resize(&small, width, height, FilterType::Triangle)
+}
+
+/// Resampled every frame. A cover fit keeps its overflow for panning;
+/// other modes are laid out on a frame-shaped canvas, zoomed as a whole.
+fn fit_resampled(
+    width: usize,
+    height: usize,
+    image: &DynamicImage,
+    zoom_max: f32,
+    config: &BackgroundConfig,
+) -> Result<Background, String> {
+    let px_per_1080p = zoom_max * width.min(height) as f32 / 1080.0;
+    if config.fit.mode != FitMode::Cover {
+        let (cw, ch) = ((width as f32 * zoom_max).ceil() as u32, (height as f32 * zoom_max).ceil() as u32);
+        let image = fit_image(image, cw, ch, config, px_per_1080p)?;
+        return Ok(Background::Resampled { image, cover: (width as f32, height as f32) });
+    }
+
+    // Resample from the most-zoomed size so zooming in stays sharp
+    let (iw, ih) = image.dimensions();
+    let fit = (width as f32 / iw as f32).max(height as f32 / ih as f32);
+    let cover = (iw as f32 * fit, ih as f32 * fit);
+    let image = resized(image, (cover.0 * zoom_max).ceil() as u32, (cover.1 * zoom_max).ceil() as u32);
+    let image = config.style.apply(image, px_per_1080p);
+
+    Ok(Background::Resampled { image, cover })
+}
+
+/// Draw the cover-fit `image` zoomed and panned about the frame centre.
+fn draw_resampled(
+    out: &mut [u8],
+    width: usize,
+    height: usize,
+    image: &RgbaImage,
+    cover: (f32, f32),
+    view: &View,
+) {
+    let (w, h) = (width as f32, height as f32);
+    // Frame pixel -> image pixel, per channel (R, G, B). Scale is uniform,
+    // so column taps are shared by every row.
+    let (sx, sy) = (image.width() as f32 / cover.0, image.height() as f32 / cover.1);
+    let scales = [view.scale * (1.0 + view.aberration), view.scale, view.scale * (1.0 - view.aberration)];
+    let columns: Vec<[Tap; 3]> = (0..width)
+        .map(|x| {
+            scales.map(|scale| {
+                Tap::new(((x as f32 + 0.5 - w / 2.0 - view.tx) / scale + cover.0 / 2.0) * sx, image.width())
+            })
+        })
+        .collect();
+    let src = image.as_raw();
+    let stride = image.width() as usize * 4;
+    let brightness = (view.brightness.clamp(0.0, 1.0) * 256.0).round() as u32;
+
+    for (y, row) in out.chunks_exact_mut(width * 4).take(height).enumerate() {
+        let rows = scales.map(|scale| {
+            Tap::new(((y as f32 + 0.5 - h / 2.0 - view.ty) / scale + cover.1 / 2.0) * sy, image.height())
+        });
+        for (pixel, taps) in row.chunks_exact_mut(4).zip(&columns) {
+            for c in 0..3 {
+                let (col, tap) = (&taps[c], &rows[c]);
+                let (fx, fy) = (col.weight, tap.weight);
+                let (top, bottom) = (tap.a * stride + c, tap.b * stride + c);
+                let (a, b) = (col.a * 4, col.b * 4);
+                let t = src[top + a] as u32 * (256 - fx) + src[top + b] as u32 * fx;
+                let m = src[bottom + a] as u32 * (256 - fx) + src[bottom + b] as u32 * fx;
+                let v = (t * (256 - fy) + m * fy + (1 << 15)) >> 16;
+                pixel[c] = ((v * brightness + 128) >> 8) as u8;
+            }
+            pixel[3] = 255;
+        }
+    }
+}
+
+/// Bilinear tap along one axis: neighbours `a`, `b` (edges clamped) and
+/// the weight of `b` in 1/256ths.
+struct Tap {
+    a: usize,
+    b: usize,
+    weight: u32,
+}
+
+impl Tap {
+    fn new(coord: f32, len: u32) -> Self {
+        let pos = coord - 0.5;
+        let base = pos.floor();
+        let max = len as i64 - 1;
+        Self {
+            a: (base as i64).clamp(0, max) as usize,
+            b: (base as i64 + 1).clamp(0, max) as usize,
+            weight: ((pos - base) * 256.0).round() as u32,
+        }
+    }
+}
+
+fn overlay_rgba(overlay: &[u8], out: &mut [u8]) {
+    for i in (0..overlay.len()).step_by(4) {
+        let alpha = overlay[i + 3];
+        if alpha == 0 {
+            continue;
+        }
+        if alpha == 255 {
+            out[i..i + 4].copy_from_slice(&overlay[i..i + 4]);
+            continue;
+        }
+        let inv = 255 - alpha;
+        for c in 0..3 {
+            let src = overlay[i + c] as u16;
+            let dst = out[i + c] as u16;
+            out[i + c] = ((src * alpha as u16 + dst * inv as u16) / 255) as u8;
+        }
+        out[i + 3] = 255;
+    }
+}
+
+fn draw_progress(
+    buffer: &mut [u8],
+    width: usize,
+    height: usize,
+    frame_index: usize,
+    total_frames: usize,
+    overlay: &OverlayConfig,
+) {
+    if !overlay.show_progress || total_frames == 0 {
+        return;
+    }
+    let ui_scale = (height as f32 / 1080.0).max(0.5);
+    let padding = (32.0 * ui_scale).round() as i32;
+    let bar_h = (8.0 * ui_scale).round().max(2.0) as i32;
+    let bar_w = width as i32 - padding * 2;
+    let y = height as i32 - padding - bar_h;
+
+    let bg_rect = Rect { x: padding, y, w: bar_w, h: bar_h };
+    draw_rect(buffer, width, height, &bg_rect, (255, 255, 255), 30);
+
+    let pct = (frame_index as f32 / total_frames as f32).clamp(0.0, 1.0);
+    let fill_w = (bar_w as f32 * pct).round() as i32;
+    let fill_rect = Rect { x: padding, y, w: fill_w, h: bar_h };
+    draw_rect(buffer, width, height, &fill_rect, overlay.accent_rgb, 255);
+}
+
+fn draw_rect(buffer: &mut [u8], width: usize, height: usize, r: &Rect, rgb: (u8, u8, u8), alpha: u8) {
+    if r.w <= 0 || r.h <= 0 {
+        return;
+    }
+    let x0 = r.x.max(0) as usize;
+    let y0 = r.y.max(0) as usize;
+    let x1 = (r.x + r.w).min(width as i32).max(0) as usize;
+    let y1 = (r.y + r.h).min(height as i32).max(0) as usize;
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    for cy in y0..y1 {
+        for cx in x0..x1 {
+            let idx = (cy * width + cx) * 4;
+            blend_pixel(&mut buffer[idx..idx + 4], rgb, alpha);
+        }
+    }
+}
+
+fn blend_pixel(pixel: &mut [u8], rgb: (u8, u8, u8), alpha: u8) {
+    if alpha == 255 {
+        pixel[0] = rgb.0;
+        pixel[1] = rgb.1;
+        pixel[2] = rgb.2;
+        pixel[3] = 255;
+        return;
+    }
+    let inv = 255 - alpha;
+    let r = (rgb.0 as u16 * alpha as u16 + pixel[0] as u16 * inv as u16) / 255;
+    let g = (rgb.1 as u16 * alpha as u16 + pixel[1] as u16 * inv as u16) / 255;
+    let b = (rgb.2 as u16 * alpha as u16 + pixel[2] as u16 * inv as u16) / 255;
+    pixel[0] = r as u8;
+    pixel[1] = g as u8;
+    pixel[2] = b as u8;
+    pixel[3] = 255;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageFormat;
+    use std::io::Cursor;
+    use std::path::Path;
+    use tempfile::tempdir;
+    use vibe_engine::{VibeEngine, VibeSettings, FFT_SIZE};
+
+    /// Compare with `tests/golden/<name>.png` (see `vibe-golden`).
+    fn assert_golden(name: &str, width: u32, height: u32, actual: &[u8]) {
+        vibe_golden::assert_golden(Path::new(env!("CARGO_MANIFEST_DIR")), name, width, height, actual);
+    }
+
+    const ACCENT: (u8, u8, u8) = (6, 182, 212);
+
+    /// Engine output for one second of a 60 Hz + 880 Hz chord at 30fps.
+    fn engine_frame(width: i32, height: i32) -> Vec<u8> {
+        let settings: VibeSettings = serde_json::from_value(serde_json::json!({
+            "visualizer_mode": "Bars",
+            "visualizer_color": "#06b6d4",
+            "visualizer_intensity": 0.8,
+            "bar_anchor": "BottomCenter",
+        }))
+        .unwrap();
+        let rate = 48_000.0;
+        let pcm: Vec<f32> = (0..rate as usize)
+            .map(|i| {
+                let t = i as f32 / rate;
+                (std::f32::consts::TAU * 60.0 * t).sin() * 0.5
+                    + (std::f32::consts::TAU * 880.0 * t).sin() * 0.1
+            })
+            .collect();
+
+        let mut engine = VibeEngine::new(width, height);
+        engine.set_sample_rate(rate);
+        for frame in 0..30 {
+            let time = frame as f64 / 30.0;
+            let end = (time * rate as f64) as usize;
+            engine.render_native(&settings, &pcm[end.saturating_sub(FFT_SIZE)..end], time);
+        }
+        engine.get_pixel_slice().to_vec()
+    }
+
+    fn at(time_secs: f64, track: usize) -> FrameContext {
+        FrameContext { time_secs, index: 40, total: 100, track, bass: 0.0, beat: 0.0 }
+    }
+
+    fn compose(composer: &mut FrameComposer, engine_pixels: &[u8], track: usize) -> Vec<u8> {
+        let mut out = vec![0u8; composer.frame_size()];
+        composer.compose_into(engine_pixels, &at(0.0, track), &mut out).unwrap();
+        out
+    }
+
+    /// Background alone (transparent engine layer) for `frame`.
+    fn background(composer: &mut FrameComposer, frame: &FrameContext) -> Vec<u8> {
+        let mut out = vec![0u8; composer.frame_size()];
+        composer.compose_into(&vec![0u8; composer.frame_size()], frame, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn golden_solid_background_with_progress() {
+        let overlay = OverlayConfig { show_progress: true, accent_rgb: ACCENT };
+        let mut composer =
+            FrameComposer::new(320, 180, "", &BackgroundConfig::default(), None, overlay, &[]).unwrap();
+        let frame = compose(&mut composer, &engine_frame(320, 180), 0);
+        assert_golden("compose_solid_progress", 320, 180, &frame);
+    }
+
+    #[test]
+    fn golden_image_background_with_text_overlay() {
+        // Landscape gradient into a portrait frame exercises cover-crop
+        let dir = tempdir().unwrap();
+        let image_path = dir.path().join("background.png");
+        RgbaImage::from_fn(64, 48, |x, y| Rgba([(x * 4) as u8, (y * 5) as u8, 96, 255]))
+            .save(&image_path)
+            .unwrap();
+
+        // Translucent caption band, encoded the way the frontend sends it
+        let caption = RgbaImage::from_fn(180, 320, |_, y| {
+            if (240..280).contains(&y) {
+                Rgba([255, 255, 255, 160])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        });
+        let mut png = Vec::new();
+        caption.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+        let caption = base64::engine::general_purpose::STANDARD.encode(png);
+
+        let overlay = OverlayConfig { show_progress: false, accent_rgb: ACCENT };
+        let mut composer = FrameComposer::new(
+            180,
+            320,
+            image_path.to_str().unwrap(),
+            &BackgroundConfig::default(),
+            None,
+            overlay,
+            &[String::new(), caption],
+        )
+        .unwrap();
+        let engine_pixels = engine_frame(180, 320);
+
+        let frame = compose(&mut composer, &engine_pixels, 1);
+        assert_golden("compose_image_overlay", 180, 320, &frame);
+
+        // Track 0 has no overlay: same frame without the caption band
+        assert_ne!(compose(&mut composer, &engine_pixels, 0), frame);
+    }
+
+    #[test]
+    fn golden_ken_burns_background() {
+        let dir = tempdir().unwrap();
+        let image_path = dir.path().join("background.png");
+        RgbaImage::from_fn(64, 48, |x, y| Rgba([(x * 4) as u8, (y * 5) as u8, 96, 255]))
+            .save(&image_path)
+            .unwrap();
+
+        let overlay = OverlayConfig { show_progress: false, accent_rgb: ACCENT };
+        let config = BackgroundConfig {
+            ken_burns: Some(KenBurns { seed: 5, zoom_max: 1.2, pan: 0.1, ..KenBurns::default() }),
+            ..BackgroundConfig::default()
+        };
+        let mut composer =
+            FrameComposer::new(320, 180, image_path.to_str().unwrap(), &config, None, overlay, &[]).unwrap();
+
+        let start = background(&mut composer, &at(0.0, 0));
+        let later = background(&mut composer, &at(40.0, 0));
+
+        assert_golden("compose_ken_burns", 320, 180, &later);
+        assert_ne!(start, later, "background moves over time");
+    }
+
+    #[test]
+    fn golden_blurred_background() {
+        // Checkerboard: blur shows as soft edges, dim/desaturate in the colours
+        let dir = tempdir().unwrap();
+        let image_path = dir.path().join("background.png");
+        RgbaImage::from_fn(64, 36, |x, y| {
+            if (x / 8 + y / 8) % 2 == 0 {
+                Rgba([240, 60, 40, 255])
+            } else {
+                Rgba([30, 90, 220, 255])
+            }
+        })
+        .save(&image_path)
+        .unwrap();
+
+        let overlay = OverlayConfig { show_progress: false, accent_rgb: ACCENT };
+        let config = BackgroundConfig { style: BackgroundStyle::blurred(), ..BackgroundConfig::default() };
+        let mut composer =
+            FrameComposer::new(320, 180, image_path.to_str().unwrap(), &config, None, overlay, &[]).unwrap();
+        let frame = background(&mut composer, &at(0.0, 0));
+        assert_golden("compose_blurred_background", 320, 180, &frame);
+    }
+
+    #[test]
+    fn golden_reactive_background_on_a_beat() {
+        let dir = tempdir().unwrap();
+        let image_path = dir.path().join("background.png");
+        RgbaImage::from_fn(64, 36, |x, y| {
+            if (x / 8 + y / 8) % 2 == 0 {
+                Rgba([230, 230, 230, 255])
+            } else {
+                Rgba([20, 20, 20, 255])
+            }
+        })
+        .save(&image_path)
+        .unwrap();
+
+        let overlay = OverlayConfig { show_progress: false, accent_rgb: ACCENT };
+        let config = BackgroundConfig {
+            reactive: ReactiveBackground { zoom_pulse: 1.0, brightness_pump: 1.0, chromatic: 1.0, shake: 1.0 },
+            ..BackgroundConfig::default()
+        };
+        let mut composer =
+            FrameComposer::new(320, 180, image_path.to_str().unwrap(), &config, None, overlay, &[]).unwrap();
+
+        let quiet = background(&mut composer, &at(1.0, 0));
+        let hit = background(&mut composer, &FrameContext { bass: 1.0, beat: 1.0, ..at(1.0, 0) });
+        assert_golden("compose_reactive_beat", 320, 180, &hit);
+
+        // Pumped: quiet frames are darker overall
+        let mean = |frame: &[u8]| frame.iter().map(|&b| b as u64).sum::<u64>() / frame.len() as u64;
+        assert!(mean(&quiet) < mean(&hit), "{} vs {}", mean(&quiet), mean(&hit));
+        // Aberration: on the hit, grey squares split into red and blue fringes
+        let coloured = |frame: &[u8]| {
+            frame.chunks_exact(4).filter(|p| (p[0] as i32 - p[2] as i32).abs() > 40).count()
+        };
+        assert_eq!(coloured(&quiet), 0);
+        assert!(coloured(&hit) > 100, "{} fringe pixels", coloured(&hit));
+    }
+
+    #[test]
+    fn animated_background_ping_pongs_with_cover_crop() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Frame};
+
+        // Wide red, green, blue frames at 100 ms each; the portrait frame
+        // crops the centre, where a white bar marks the cover-fit middle
+        let dir = tempdir().unwrap();
+        let image_path = dir.path().join("background.gif");
+        let colours = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+        let frames = colours.map(|colour| {
+            let image = RgbaImage::from_fn(80, 40, |x, _| {
+                if (38..42).contains(&x) { Rgba([255, 255, 255, 255]) } else { Rgba(colour) }
+            });
+            Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(100, 1))
+        });
+        GifEncoder::new(std::fs::File::create(&image_path).unwrap()).encode_frames(frames).unwrap();
+
+        let overlay = OverlayConfig { show_progress: false, accent_rgb: ACCENT };
+        let config = BackgroundConfig { playback: Playback::PingPong, ..BackgroundConfig::default() };
+        let mut composer =
+            FrameComposer::new(20, 40, image_path.to_str().unwrap(), &config, None, overlay, &[]).unwrap();
+
+        // Forward 0 1 2, back 1, then around again
+        let mut pixel = |time: f64, x: usize| {
+            let frame = background(&mut composer, &at(time, 0));
+            let i = (20 * 20 + x) * 4;
+            [frame[i], frame[i + 1], frame[i + 2]]
+        };
+        let shown: Vec<_> = [0.05, 0.15, 0.25, 0.35, 0.45].map(|t| pixel(t, 2)).to_vec();
+        assert_eq!(shown, [[255, 0, 0], [0, 255, 0], [0, 0, 255], [0, 255, 0], [255, 0, 0]]);
+        assert_eq!(pixel(0.05, 10), [255, 255, 255]);
+    }
+
+    /// Square "album art" with a white border, so lost edges show.
+    fn album_art(dir: &std::path::Path) -> String {
+        let path = dir.join("album.png");
+        RgbaImage::from_fn(64, 64, |x, y| {
+            if x < 3 || y < 3 || x > 60 || y > 60 {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([(x * 4) as u8, 40, (y * 4) as u8, 255])
+            }
+        })
+        .save(&path)
+        .unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn golden_contain_and_blurred_fill() {
+        let dir = tempdir().unwrap();
+        let image_path = album_art(dir.path());
+        let compose_fit = |fit: BackgroundFit| {
+            let overlay = OverlayConfig { show_progress: false, accent_rgb: ACCENT };
+            let config = BackgroundConfig { fit, ..BackgroundConfig::default() };
+            let mut composer = FrameComposer::new(320, 180, &image_path, &config, None, overlay, &[]).unwrap();
+            background(&mut composer, &at(0.0, 0))
+        };
+
+        let contain = compose_fit(BackgroundFit {
+            mode: FitMode::Contain,
+            fill: Fill::Gradient("#203040".into(), "#000000".into()),
+            ..BackgroundFit::default()
+        });
+        assert_golden("compose_fit_contain", 320, 180, &contain);
+
+        let blurred = compose_fit(BackgroundFit { mode: FitMode::BlurredFill, ..BackgroundFit::default() });
+        assert_golden("compose_fit_blurred_fill", 320, 180, &blurred);
+    }
+
+    #[test]
+    fn fit_modes_keep_focus_tile_and_fill() {
+        let dir = tempdir().unwrap();
+        let image_path = album_art(dir.path());
+        let white = |frame: &[u8], x: usize, y: usize| {
+            // Border pixels, allowing for resampling ringing
+            let i = (y * 320 + x) * 4;
+            frame[i..i + 3].iter().all(|&c| c > 230)
+        };
+        let compose_fit = |fit: BackgroundFit| {
+            let overlay = OverlayConfig { show_progress: false, accent_rgb: ACCENT };
+            let config = BackgroundConfig { fit, ..BackgroundConfig::default() };
+            FrameComposer::new(320, 180, &image_path, &config, None, overlay, &[])
+                .map(|mut composer| background(&mut composer, &at(0.0, 0)))
+        };
+
+        // Cover crops the square to 16:9: centred loses the top border,
+        // focus at the top keeps it
+        let centred = compose_fit(BackgroundFit::default()).unwrap();
+        let top = compose_fit(BackgroundFit { focus: (0.5, 0.0), ..BackgroundFit::default() }).unwrap();
+        assert!(!white(&centred, 160, 2));
+        assert!(white(&top, 160, 2));
+
+        // Tiles of 90px: one centred, so borders at 115 and 205 across
+        let tiled = compose_fit(BackgroundFit { mode: FitMode::Tile, ..BackgroundFit::default() }).unwrap();
+        assert!(white(&tiled, 116, 90));
+        assert!(white(&tiled, 26, 90));
+        assert!(!white(&tiled, 160, 90));
+        // Focus at the top-left starts the tiling at the frame's corner
+        let corner = BackgroundFit { mode: FitMode::Tile, focus: (0.0, 0.0), ..BackgroundFit::default() };
+        let tiled = compose_fit(corner).unwrap();
+        assert!(white(&tiled, 91, 45));
+        assert!(!white(&tiled, 116, 45));
+        // A thin strip's tile is half the short side across but no longer
+        // than the frame: 540x270000 unclamped
+        let scale = tile_scale(1920, 1080, 4, 2000);
+        assert_eq!(((4.0 * scale).round(), (2000.0 * scale).round()), (4.0, 1920.0));
+
+        let stretched = compose_fit(BackgroundFit { mode: FitMode::Stretch, ..BackgroundFit::default() }).unwrap();
+        assert!(white(&stretched, 3, 90));
+        assert!(white(&stretched, 160, 2));
+
+        let bad = BackgroundFit { fill: Fill::Solid("teal".into()), ..BackgroundFit::default() };
+        assert!(compose_fit(bad).unwrap_err().contains("invalid fill colour"));
+    }
+
+    #[test]
+    fn background_style_scales_blur_and_dims() {
+        let edge = RgbaImage::from_fn(200, 10, |x, _| {
+            if x < 100 { Rgba([255, 255, 255, 255]) } else { Rgba([0, 0, 0, 255]) }
+        });
+        let blur = BackgroundStyle { blur: 4.0, ..BackgroundStyle::default() };
+        // Twice the resolution: the transition spreads over twice the pixels
+        let spread = |image: &RgbaImage| image.pixels().filter(|p| (20..235).contains(&p.0[0])).count();
+        let (small, large) = (blur.apply(edge.clone(), 1.0), blur.apply(edge.clone(), 2.0));
+        assert!(spread(&large) > spread(&small) * 3 / 2, "{} vs {}", spread(&large), spread(&small));
+
+        let dim = BackgroundStyle { dim: 0.5, desaturate: 1.0, ..BackgroundStyle::default() };
+        let red = dim.apply(RgbaImage::from_pixel(1, 1, Rgba([255, 0, 0, 255])), 1.0);
+        // Greyscale at the red luma (0.2126 * 255 ≈ 54), then halved
+        assert_eq!(red.get_pixel(0, 0).0, [27, 27, 27, 255]);
+    }
+
+    #[test]
+    fn ken_burns_stays_in_range_and_seed_flips_direction() {
+        let motion = KenBurns::default();
+        let flipped = KenBurns { seed: 0b111, ..KenBurns::default() };
+        for step in 0..200 {
+            let time = step as f64 * 3.7;
+            let (scale, tx, ty) = motion.transform(time, 1080.0);
+            assert!((1.03..=1.07).contains(&scale), "scale {scale} at {time}s");
+            assert!(tx.abs() <= 15.2 && ty.abs() <= 15.2);
+
+            let (flipped_scale, ftx, fty) = flipped.transform(time, 1080.0);
+            assert_eq!((ftx, fty), (-tx, -ty));
+            assert!((flipped_scale - (2.1 - scale)).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! Video export pipeline - decode audio, render frames, pipe to FFmpeg.
+//!
+//! Independent of Tauri: the desktop app's `export_video` command and the
+//! `vibe-machine-cli` binary both call `render_video`, so a script produces
+//! the same MP4 as the app.
+
+mod animation;
+mod audio;
+mod ffmpeg;
+mod frame;
+mod project;
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use audio::{analyze_tempo, decode_tracks};
+pub use animation::Playback;
+use ffmpeg::{Encoder, VideoDecoder};
+pub use frame::{BackgroundFit, BackgroundStyle, Fill, FitMode, KenBurns, ReactiveBackground};
+use frame::{BackgroundConfig, FrameComposer, FrameContext, OverlayConfig};
+pub use project::{
+    Project, ProjectBackground, ProjectOutput, ProjectOverlay, ProjectTrack, PROJECT_VERSION,
+};
+use vibe_engine::{VibeEngine, VibeSettings, FFT_SIZE};
+
+/// Frequency range (Hz) driving the reactive background's bass effects.
+const BASS_HZ: (f32, f32) = (20.0, 150.0);
+
+#[derive(Clone, serde::Serialize)]
+pub struct ExportProgress {
+    pub progress: f32,
+    pub status: String,
+    pub state: ExportState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportState {
+    Running,
+    Done,
+    Cancelled,
+}
+
+impl ExportProgress {
+    fn running(progress: f32, status: impl Into<String>) -> Self {
+        Self { progress, status: status.into(), state: ExportState::Running }
+    }
+}
+
+/// Stops a `render_video` from another thread. Clones share one flag.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Everything needed to render one video. Paths are used as given; callers
+/// taking untrusted input validate them first.
+pub struct RenderJob {
+    /// Audio files to concatenate, in order.
+    pub audio_paths: Vec<PathBuf>,
+    pub image_path: Option<PathBuf>,
+    pub output_path: PathBuf,
+    pub settings: VibeSettings,
+    pub fps: u32,
+    pub width: i32,
+    pub height: i32,
+    pub show_progress: bool,
+    /// Fit mode, crop focus and fill colour of the background.
+    pub background_fit: BackgroundFit,
+    /// Background pan/zoom; `None` keeps the image still.
+    pub ken_burns: Option<KenBurns>,
+    /// Blur/dim/desaturate for the background image.
+    pub background_style: BackgroundStyle,
+    /// Music-driven background effects (default: off).
+    pub reactive_background: ReactiveBackground,
+    /// Repeat mode when the background is a GIF, APNG or video.
+    pub background_playback: Playback,
+    /// Text overlay PNG (base64) for each track - indexed by track order
+    pub track_overlays: Vec<String>,
+    /// Cumulative track end times in seconds, for switching text overlays.
+    /// Empty = taken from the decoded tracks.
+    pub track_boundaries: Vec<f64>,
+}
+
+impl RenderJob {
+    /// Re-render a saved project to `output_path`. Text overlays are drawn
+    /// by the app's frontend, so none are included.
+    pub fn from_project(project: Project, output_path: PathBuf) -> Self {
+        Self {
+            audio_paths: project.tracks.into_iter().map(|t| t.path).collect(),
+            image_path: project.background.image,
+            output_path,
+            settings: project.settings,
+            fps: project.output.fps,
+            width: project.output.width,
+            height: project.output.height,
+            show_progress: project.output.show_progress,
+            background_fit: project.background.fit,
+            ken_burns: project
+                .background
+                .ken_burns
+                .then_some(project.background.ken_burns_motion),
+            background_style: if project.background.blur {
+                project.background.style
+            } else {
+                BackgroundStyle::default()
+            },
+            reactive_background: if project.background.reactive {
+                project.background.reactive_strengths
+            } else {
+                ReactiveBackground::default()
+            },
+            background_playback: project.background.playback,
+            track_overlays: Vec::new(),
+            track_boundaries: Vec::new(),
+        }
+    }
+}
+
+/// Decode, render and encode `job` with `ffmpeg` (a command for the binary,
+/// arguments are added here). Blocks until FFmpeg exits.
+///
+/// `cancel` is checked between the setup stages (audio decode, tempo
+/// analysis, background load) and once per rendered frame; a cancelled
+/// export kills FFmpeg, deletes the partial output, reports an
+/// `ExportState::Cancelled` progress event and returns `Ok`.
+pub fn render_video(
+    job: &RenderJob,
+    ffmpeg: Command,
+    cancel: &CancelToken,
+    mut on_progress: impl FnMut(ExportProgress),
+) -> Result<(), String> {
+    let start_time = std::time::Instant::now();
+    let fps = job.fps.max(1);
+
+    // 1. Decode ALL audio files BEFORE spawning FFmpeg (fail-fast on errors/mismatches)
+    on_progress(ExportProgress::running(
+        0.0,
+        format!("Decoding {} tracks...", job.audio_paths.len()),
+    ));
+
+    let audio = decode_tracks(&job.audio_paths)?;
+    log::info!(
+        "Decoded {} samples at {}Hz from {} tracks",
+        audio.left.len(),
+        audio.sample_rate,
+        job.audio_paths.len()
+    );
+    if setup_cancelled(cancel, &mut on_progress) {
+        return Ok(());
+    }
+
+    on_progress(ExportProgress::running(0.0, "Analyzing tempo..."));
+    let tempo_map = analyze_tempo(&audio);
+    if setup_cancelled(cancel, &mut on_progress) {
+        return Ok(());
+    }
+
+    // 2. Setup FrameComposer BEFORE spawning FFmpeg (fail-fast)
+    let mut engine = VibeEngine::new(job.width, job.height);
+    engine.set_sample_rate(audio.sample_rate as f32);
+    engine.set_tempo_map(tempo_map);
+
+    let overlay = OverlayConfig {
+        show_progress: job.show_progress,
+        accent_rgb: hex_to_rgb(&job.settings.visualizer_color),
+    };
+    let image_path = match &job.image_path {
+        Some(path) => path.to_str().ok_or("Invalid image path encoding")?,
+        None => "",
+    };
+    let background = BackgroundConfig {
+        fit: job.background_fit.clone(),
+        ken_burns: job.ken_burns.clone(),
+        style: job.background_style.clone(),
+        reactive: job.reactive_background.clone(),
+        playback: job.background_playback,
+    };
+    // Video backgrounds are streamed from the same FFmpeg, one frame per video frame
+    let video = VideoDecoder { ffmpeg: &ffmpeg, fps };
+    let mut composer = FrameComposer::new(
+        job.width,
+        job.height,
+        image_path,
+        &background,
+        Some(&video),
+        overlay,
+        &job.track_overlays,
+    )?;
+    if setup_cancelled(cancel, &mut on_progress) {
+        return Ok(());
+    }
+    // Composer owns the size; callers don't compute independently
+    let mut frame = vec![0u8; composer.frame_size()];
+
+    // Compute timing: use f64 for precision, avoid cumulative drift
+    let audio_duration_secs = audio.left.len() as f64 / audio.sample_rate as f64;
+    let total_frames = (audio_duration_secs * fps as f64).ceil() as usize;
+    let samples_per_frame_f64 = audio.sample_rate as f64 / fps as f64;
+
+    let track_boundaries = if job.track_boundaries.is_empty() {
+        audio
+            .track_ends
+            .iter()
+            .map(|&end| end as f64 / audio.sample_rate as f64)
+            .collect()
+    } else {
+        job.track_boundaries.clone()
+    };
+
+    // 3. NOW spawn FFmpeg - all validation complete, nothing can fail before render loop
+    let mut encoder = Encoder::spawn(
+        ffmpeg,
+        job.width,
+        job.height,
+        fps,
+        &job.audio_paths,
+        &job.output_path,
+    )?;
+
+    // Helper to find active track index based on current time
+    let find_active_track = |time_secs: f64| -> usize {
+        track_boundaries
+            .iter()
+            .position(|&boundary| time_secs < boundary)
+            .unwrap_or(track_boundaries.len().saturating_sub(1))
+    };
+
+    for i in 0..total_frames {
+        if cancel.is_cancelled() {
+            encoder.abort();
+            report_cancelled(&mut on_progress, i as f32 / total_frames as f32);
+            return Ok(());
+        }
+
+        // Float accumulator prevents A/V sync drift from integer rounding
+        let sample_idx = (i as f64 * samples_per_frame_f64).floor() as usize;
+        let current_time_secs = i as f64 / fps as f64;
+
+        // Determine which track is currently playing
+        let active_track_index = find_active_track(current_time_secs);
+
+        // Latest FFT_SIZE samples up to now, like AnalyserNode; the engine
+        // runs the same analysis as the preview
+        let window_end = sample_idx.min(audio.left.len());
+        let window = window_end.saturating_sub(FFT_SIZE)..window_end;
+
+        // Engine Render
+        engine.render_stereo_native(
+            &job.settings,
+            &audio.left[window.clone()],
+            &audio.right[window],
+            current_time_secs,
+        );
+
+        // Compose frame (background + viz + overlays) then write
+        let pixels = engine.get_pixel_slice();
+        let context = FrameContext {
+            time_secs: current_time_secs,
+            index: i,
+            total: total_frames,
+            track: active_track_index,
+            bass: engine.band_energy(BASS_HZ.0, BASS_HZ.1),
+            beat: engine.beat_strength(),
+        };
+        if let Err(e) = composer.compose_into(pixels, &context, &mut frame) {
+            encoder.abort();
+            return Err(e);
+        }
+        if let Err(e) = encoder.write_frame(&frame) {
+            // A closed pipe usually means FFmpeg failed; prefer its message
+            return Err(encoder.finish().err().unwrap_or(e));
+        }
+
+        if i % 30 == 0 {
+            on_progress(ExportProgress::running(
+                i as f32 / total_frames as f32,
+                "Rendering Video...",
+            ));
+        }
+    }
+
+    encoder.finish()?;
+
+    let elapsed = start_time.elapsed().as_secs_f32();
+    log::info!("Export completed in {:.1}s", elapsed);
+
+    on_progress(ExportProgress {
+        progress: 1.0,
+        status: format!("Done in {:.1}s", elapsed),
+        state: ExportState::Done,
+    });
+
+    Ok(())
+}
+
+/// Before any output exists: reports the cancel and returns `true` if set.
+fn setup_cancelled(cancel: &CancelToken, on_progress: &mut impl FnMut(ExportProgress)) -> bool {
+    if cancel.is_cancelled() {
+        report_cancelled(on_progress, 0.0);
+    }
+    cancel.is_cancelled()
+}
+
+fn report_cancelled(on_progress: &mut impl FnMut(ExportProgress), progress: f32) {
+    log::info!("Export cancelled");
+    on_progress(ExportProgress {
+        progress,
+        status: "Cancelled".into(),
+        state: ExportState::Cancelled,
+    });
+}
+
+fn hex_to_rgb(hex: &str) -> (u8, u8, u8) {
+    const FALLBACK: (u8, u8, u8) = (255, 183, 3); // Plasma fallback
+
+    let h = hex.trim_start_matches('#');
+    if h.len() != 6 {
+        return FALLBACK;
+    }
+
+    if let (Ok(r), Ok(g), Ok(b)) = (
+        u8::from_str_radix(&h[0..2], 16),
+        u8::from_str_radix(&h[2..4], 16),
+        u8::from_str_radix(&h[4..6], 16),
+    ) {
+        (r, g, b)
+    } else {
+        FALLBACK
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// One second of 16-bit mono silence as a WAV file.
+    fn write_wav(path: &std::path::Path) {
+        let rate: u32 = 8_000;
+        let data = vec![0u8; rate as usize * 2];
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&rate.to_le_bytes());
+        wav.extend_from_slice(&(rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+        std::fs::write(path, wav).unwrap();
+    }
+
+    /// A 64x36 Bars job over one second of silence in `dir`.
+    fn silent_job(dir: &std::path::Path) -> RenderJob {
+        let audio = dir.join("silence.wav");
+        write_wav(&audio);
+        RenderJob {
+            audio_paths: vec![audio],
+            image_path: None,
+            output_path: dir.join("out.mp4"),
+            settings: serde_json::from_str(
+                r##"{ "visualizer_mode": "Bars", "visualizer_color": "#ffffff", "visualizer_intensity": 0.5 }"##,
+            )
+            .unwrap(),
+            fps: 60,
+            width: 64,
+            height: 36,
+            show_progress: false,
+            background_fit: BackgroundFit::default(),
+            ken_burns: None,
+            background_style: BackgroundStyle::default(),
+            reactive_background: ReactiveBackground::default(),
+            background_playback: Playback::default(),
+            track_overlays: Vec::new(),
+            track_boundaries: Vec::new(),
+        }
+    }
+
+    /// Stand-in encoder: copies stdin to the last argument (the output).
+    fn stub_encoder() -> Command {
+        let mut ffmpeg = Command::new("sh");
+        ffmpeg.args(["-c", r#"for a; do out="$a"; done; cat > "$out""#, "sh"]);
+        ffmpeg
+    }
+
+    #[test]
+    fn cancel_stops_encoder_and_removes_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = silent_job(dir.path());
+
+        let cancel = CancelToken::default();
+        let mut states = Vec::new();
+        render_video(&job, stub_encoder(), &cancel, |p| {
+            // Cancel once frames are flowing, so there is a partial file
+            if p.status == "Rendering Video..." && p.progress > 0.0 {
+                cancel.cancel();
+            }
+            states.push(p.state);
+        })
+        .unwrap();
+
+        assert_eq!(states.last(), Some(&ExportState::Cancelled));
+        assert!(!states.contains(&ExportState::Done));
+        assert!(!job.output_path.exists(), "partial output left behind");
+    }
+
+    #[test]
+    fn cancel_during_setup_never_starts_the_encoder() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = silent_job(dir.path());
+
+        let cancel = CancelToken::default();
+        let mut statuses = Vec::new();
+        render_video(&job, stub_encoder(), &cancel, |p| {
+            if p.status == "Analyzing tempo..." {
+                cancel.cancel();
+            }
+            statuses.push((p.status, p.state));
+        })
+        .unwrap();
+
+        assert_eq!(statuses.last().map(|s| s.1), Some(ExportState::Cancelled));
+        assert!(!statuses.iter().any(|(status, _)| status.starts_with("Rendering")));
+        assert!(!job.output_path.exists(), "encoder started after cancel");
+    }
+}
@@ -0,0 +1,301 @@
+//! `.vibe` project files: a whole session (tracks, background, engine
+//! settings, text overlay and output options) as versioned JSON.
+//!
+//! Paths inside the project's folder are stored relative to it, so the
+//! folder can be moved or shared as a unit; anything else stays absolute.
+//! Files from older versions are migrated step by step on load.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use vibe_engine::VibeSettings;
+
+use crate::animation::Playback;
+use crate::frame::{BackgroundFit, BackgroundStyle, KenBurns, ReactiveBackground};
+
+/// Written into every saved project.
+pub const PROJECT_VERSION: u32 = 1;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// `MIGRATIONS[i]` upgrades a version `i + 1` document to version `i + 2`.
+const MIGRATIONS: &[Migration] = &[];
+
+const _: () = assert!(MIGRATIONS.len() as u32 == PROJECT_VERSION - 1);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Project {
+    /// Schema version; `save` always writes `PROJECT_VERSION`. Files
+    /// without one load as version 1 (`migrate`); the default is for
+    /// projects the app builds in memory and sends to `save`.
+    #[serde(default = "current_version")]
+    pub version: u32,
+    /// In playback order.
+    pub tracks: Vec<ProjectTrack>,
+    #[serde(default)]
+    pub background: ProjectBackground,
+    /// Engine settings, exactly as passed to the renderer.
+    pub settings: VibeSettings,
+    #[serde(default)]
+    pub overlay: ProjectOverlay,
+    pub output: ProjectOutput,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectTrack {
+    pub path: PathBuf,
+    pub name: String,
+    #[serde(default)]
+    pub artist: String,
+    /// Seconds, as measured when the track was added.
+    #[serde(default)]
+    pub duration: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProjectBackground {
+    #[serde(default)]
+    pub image: Option<PathBuf>,
+    /// Fit mode, crop focus and fill colour.
+    #[serde(default)]
+    pub fit: BackgroundFit,
+    #[serde(default)]
+    pub ken_burns: bool,
+    /// Speed, zoom range and directions, kept while `ken_burns` is off.
+    #[serde(default)]
+    pub ken_burns_motion: KenBurns,
+    #[serde(default)]
+    pub blur: bool,
+    /// Blur radius, dim and desaturate, kept while `blur` is off. Files
+    /// without it get the original fixed look.
+    #[serde(default = "BackgroundStyle::blurred")]
+    pub style: BackgroundStyle,
+    /// Music-driven zoom, pump, aberration and shake.
+    #[serde(default)]
+    pub reactive: bool,
+    /// Per-effect strengths, kept while `reactive` is off. Files without
+    /// them get the original fixed mix.
+    #[serde(default = "ReactiveBackground::pulse")]
+    pub reactive_strengths: ReactiveBackground,
+    /// Repeat mode of a GIF, APNG or video background.
+    #[serde(default)]
+    pub playback: Playback,
+}
+
+/// Title text drawn by the app over each track.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectOverlay {
+    pub show_title: bool,
+    pub font_family: String,
+    /// Multiplier on the base title size.
+    pub font_size: f32,
+}
+
+impl Default for ProjectOverlay {
+    fn default() -> Self {
+        Self {
+            show_title: true,
+            font_family: "Geist Sans".into(),
+            font_size: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectOutput {
+    pub width: i32,
+    pub height: i32,
+    pub fps: u32,
+    #[serde(default)]
+    pub show_progress: bool,
+}
+
+fn current_version() -> u32 {
+    PROJECT_VERSION
+}
+
+impl Project {
+    /// Read `path`, migrate it to `PROJECT_VERSION` and resolve relative
+    /// paths against its folder.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read project {}: {}", path.display(), e))?;
+        Self::from_json(&text, project_dir(path))
+    }
+
+    /// Write to `path`, storing paths inside its folder relative to it.
+    /// The file is replaced atomically, so a failed save keeps the old one.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let dir = project_dir(path);
+        let text = self.to_json(dir)?;
+        let mut file = tempfile::NamedTempFile::new_in(dir)
+            .map_err(|e| format!("Failed to save project: {}", e))?;
+        std::io::Write::write_all(&mut file, text.as_bytes())
+            .map_err(|e| format!("Failed to save project: {}", e))?;
+        file.persist(path)
+            .map_err(|e| format!("Failed to save project: {}", e))?;
+        Ok(())
+    }
+
+    /// Referenced files that no longer exist.
+    pub fn missing_files(&self) -> Vec<PathBuf> {
+        self.paths().filter(|p| !p.is_file()).cloned().collect()
+    }
+
+    fn from_json(text: &str, dir: &Path) -> Result<Self, String> {
+        let mut doc: Value =
+            serde_json::from_str(text).map_err(|e| format!("Invalid project file: {}", e))?;
+        migrate(&mut doc)?;
+        let mut project: Self =
+            serde_json::from_value(doc).map_err(|e| format!("Invalid project file: {}", e))?;
+        project.map_paths(|p| dir.join(p));
+        Ok(project)
+    }
+
+    fn to_json(&self, dir: &Path) -> Result<String, String> {
+        let mut doc = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        doc["version"] = PROJECT_VERSION.into();
+
+        // Relativize on the serialized copy; `self` keeps absolute paths
+        let mut stored: Self = serde_json::from_value(doc).map_err(|e| e.to_string())?;
+        stored.map_paths(|p| relative_to(p, dir));
+        serde_json::to_string_pretty(&stored).map_err(|e| e.to_string())
+    }
+
+    fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.tracks
+            .iter()
+            .map(|t| &t.path)
+            .chain(self.background.image.as_ref())
+    }
+
+    fn map_paths(&mut self, f: impl Fn(&Path) -> PathBuf) {
+        for track in &mut self.tracks {
+            track.path = f(&track.path);
+        }
+        if let Some(image) = &mut self.background.image {
+            *image = f(image);
+        }
+    }
+}
+
+fn project_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new("."))
+}
+
+/// `path` relative to `dir` with `/` separators when it is inside `dir`;
+/// otherwise unchanged.
+fn relative_to(path: &Path, dir: &Path) -> PathBuf {
+    match path.strip_prefix(dir) {
+        Ok(rel) if rel.components().all(|c| matches!(c, Component::Normal(_))) => {
+            let parts: Vec<_> = rel.iter().map(|c| c.to_string_lossy()).collect();
+            PathBuf::from(parts.join("/"))
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Bring a parsed document up to `PROJECT_VERSION`.
+fn migrate(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("Invalid project file: not a JSON object")?;
+    // Unversioned files predate the field: the first format
+    let version = match obj.get("version") {
+        None => 1,
+        Some(v) => v.as_u64().ok_or("Invalid project file: version is not a number")?,
+    };
+
+    if version == 0 || version > PROJECT_VERSION as u64 {
+        return Err(format!(
+            "Project version {} is not supported (this build reads up to {}); it may be from a newer release",
+            version, PROJECT_VERSION
+        ));
+    }
+
+    for step in &MIGRATIONS[version as usize - 1..] {
+        step(obj)?;
+    }
+    obj.insert("version".into(), PROJECT_VERSION.into());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn project(dir: &Path) -> Project {
+        serde_json::from_value(json!({
+            "tracks": [
+                { "path": dir.join("audio/one.mp3"), "name": "One", "duration": 181.5 },
+                { "path": "/elsewhere/two.flac", "name": "Two", "artist": "B" },
+            ],
+            "background": {
+                "image": dir.join("cover.jpg"),
+                "ken_burns": true,
+                "ken_burns_motion": { "speed": 2.0, "seed": 5 },
+            },
+            "settings": { "visualizer_mode": "Orbital", "visualizer_color": "#06b6d4", "visualizer_intensity": 0.7 },
+            "output": { "width": 1080, "height": 1920, "fps": 30 },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn paths_inside_the_folder_are_stored_relative() {
+        let dir = Path::new("/projects/mix");
+        let text = project(dir).to_json(dir).unwrap();
+        let doc: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(doc["version"], PROJECT_VERSION);
+        assert_eq!(doc["tracks"][0]["path"], "audio/one.mp3");
+        assert_eq!(doc["tracks"][1]["path"], "/elsewhere/two.flac");
+        assert_eq!(doc["background"]["image"], "cover.jpg");
+
+        // Reopened from a moved folder
+        let moved = Path::new("/archive/mix");
+        let loaded = Project::from_json(&text, moved).unwrap();
+        assert_eq!(loaded.tracks[0].path, moved.join("audio/one.mp3"));
+        assert_eq!(loaded.tracks[1].path, Path::new("/elsewhere/two.flac"));
+        assert_eq!(loaded.background.image.as_deref(), Some(moved.join("cover.jpg").as_path()));
+        assert_eq!(loaded.tracks[0].duration, 181.5);
+        assert_eq!(loaded.output.height, 1920);
+    }
+
+    #[test]
+    fn save_and_load_round_trip_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.vibe");
+        project(dir.path()).save(&path).unwrap();
+
+        let loaded = Project::load(&path).unwrap();
+        assert_eq!(loaded.tracks.len(), 2);
+        assert!(loaded.background.ken_burns);
+        let motion = &loaded.background.ken_burns_motion;
+        assert_eq!((motion.speed, motion.zoom_max, motion.seed), (2.0, 1.07, 5));
+        assert_eq!(loaded.background.style.blur, 12.0, "missing style keeps the fixed blur");
+        assert_eq!(loaded.background.reactive_strengths.shake, 0.3);
+        assert!(loaded.overlay.show_title, "missing overlay takes defaults");
+        assert_eq!(loaded.missing_files().len(), 3);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let newer = json!({ "version": PROJECT_VERSION + 1 }).to_string();
+        let err = Project::from_json(&newer, Path::new("/")).unwrap_err();
+        assert!(err.contains("newer release"), "{}", err);
+        assert!(Project::from_json("{}", Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn files_without_a_version_load_as_version_1() {
+        let mut doc = serde_json::to_value(project(Path::new("/mix"))).unwrap();
+        doc.as_object_mut().unwrap().remove("version");
+        let loaded = Project::from_json(&doc.to_string(), Path::new("/mix")).unwrap();
+        assert_eq!(loaded.version, PROJECT_VERSION);
+        assert_eq!(loaded.tracks.len(), 2);
+
+        let bad = json!({ "version": "one" }).to_string();
+        let err = Project::from_json(&bad, Path::new("/")).unwrap_err();
+        assert!(err.contains("not a number"), "{}", err);
+    }
+}
@@ -0,0 +1,86 @@
+//! Golden-frame comparison for the engine and composer tests.
+//!
+//! References live in `<crate>/tests/golden/`. On mismatch the actual frame
+//! and a diff (red where pixels differ, the reference dimmed elsewhere) are
+//! written under `<crate>/target/tmp/golden/`. After an intended visual
+//! change, regenerate with `UPDATE_GOLDEN=1` and review the new PNGs.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Per-channel difference tolerated as float noise.
+pub const CHANNEL_TOLERANCE: u8 = 4;
+/// Fraction of pixels allowed past `CHANNEL_TOLERANCE` (anti-aliased and
+/// resampled edges).
+pub const MAX_MISMATCH: f64 = 0.002;
+
+/// Compare an RGBA frame with `<manifest_dir>/tests/golden/<name>.png`, or
+/// rewrite the reference when `UPDATE_GOLDEN` is set.
+pub fn assert_golden(manifest_dir: &Path, name: &str, width: u32, height: u32, actual: &[u8]) {
+    assert_eq!(actual.len(), (width * height * 4) as usize, "{name}: frame size differs");
+    let reference_path = manifest_dir.join("tests/golden").join(format!("{name}.png"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        write_png(&reference_path, width, height, actual);
+        return;
+    }
+
+    let out_dir = manifest_dir.join("target/tmp/golden");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let actual_path = out_dir.join(format!("{name}.actual.png"));
+
+    let Some((ref_w, ref_h, expected)) = read_png(&reference_path) else {
+        write_png(&actual_path, width, height, actual);
+        panic!(
+            "{name}: no reference at {}; actual frame written to {}. Run with UPDATE_GOLDEN=1 to accept it",
+            reference_path.display(),
+            actual_path.display()
+        );
+    };
+    assert_eq!((ref_w, ref_h), (width, height), "{name}: reference size differs");
+
+    let mut diff = Vec::with_capacity(actual.len());
+    let mut mismatched = 0;
+    let mut worst = 0;
+    for (a, e) in actual.chunks_exact(4).zip(expected.chunks_exact(4)) {
+        let delta = a.iter().zip(e).map(|(&x, &y)| x.abs_diff(y)).max().unwrap();
+        worst = worst.max(delta);
+        if delta > CHANNEL_TOLERANCE {
+            mismatched += 1;
+            diff.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let gray = ((e[0] as u16 + e[1] as u16 + e[2] as u16) / 3 * e[3] as u16 / 255 / 3) as u8;
+            diff.extend_from_slice(&[gray, gray, gray, 255]);
+        }
+    }
+
+    let fraction = mismatched as f64 / (width * height) as f64;
+    if fraction > MAX_MISMATCH {
+        let diff_path = out_dir.join(format!("{name}.diff.png"));
+        write_png(&actual_path, width, height, actual);
+        write_png(&diff_path, width, height, &diff);
+        panic!(
+            "{name}: {mismatched} pixels ({:.2}%) differ by more than {CHANNEL_TOLERANCE} (worst {worst}); see {} and {}",
+            fraction * 100.0,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+fn write_png(path: &Path, width: u32, height: u32, rgba: &[u8]) {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path).unwrap()), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().unwrap().write_image_data(rgba).unwrap();
+}
+
+fn read_png(path: &Path) -> Option<(u32, u32, Vec<u8>)> {
+    let decoder = png::Decoder::new(File::open(path).ok()?);
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).ok()?;
+    assert_eq!(info.color_type, png::ColorType::Rgba, "{} is not RGBA", path.display());
+    buf.truncate(info.buffer_size());
+    Some((info.width, info.height, buf))
+}
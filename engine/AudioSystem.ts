@@ -24,6 +24,7 @@ import { isTauri, tauriConvertFileSrc } from "../platform/tauriEnv";
 export class AudioSystem {
   private context: AudioContext | null = null;
   private analyser: AnalyserNode | null = null;
+  private channelAnalysers: [AnalyserNode, AnalyserNode] | null = null;
   private gainNode: GainNode | null = null;
   private sourceNode: MediaElementAudioSourceNode | null = null;
   private audioEl: HTMLAudioElement;
@@ -73,6 +74,11 @@ export class AudioSystem {
     return this.analyser;
   }
 
+  /** Left and right taps for stereo visualizers; mono sources feed both. */
+  public getChannelAnalysers(): [AnalyserNode, AnalyserNode] | null {
+    return this.channelAnalysers;
+  }
+
   // Public unlock method to be called from UI click handlers
   public async unlock() {
     await this.initContext();
@@ -95,6 +101,17 @@ export class AudioSystem {
       this.sourceNode.connect(this.analyser);
       this.analyser.connect(this.gainNode);
       this.gainNode.connect(this.context.destination);
+
+      // Side branch for per-channel analysis ("speakers" upmixes mono to L = R)
+      const splitter = this.context.createChannelSplitter(2);
+      splitter.channelInterpretation = "speakers";
+      this.sourceNode.connect(splitter);
+      const left = this.context.createAnalyser();
+      const right = this.context.createAnalyser();
+      left.fftSize = right.fftSize = 2048;
+      splitter.connect(left, 0);
+      splitter.connect(right, 1);
+      this.channelAnalysers = [left, right];
       console.log("[AudioSystem] Audio Graph connected.");
     }
 
@@ -2,7 +2,7 @@
  * ExportController - Deep module owning the export UX flow.
  *
  * Hides: dialogs, path handling, IPC params, progress listener, state transitions.
- * Exposes: isSupported(), attachProgressListener(), startExport()
+ * Exposes: isSupported(), attachProgressListener(), startExport(), cancelExport()
  *
  * ## State Machine (see docs/STATE_FLOWS.md for Mermaid diagram)
  *
@@ -10,6 +10,7 @@
  * Idle -> GuardPlatform -> GuardConcurrency -> GuardTrack -> OutputDialog
  *      -> ResolveAudio -> [AudioDialog] -> Rendering -> Progress -> Complete -> Idle
  *                                                    \-> Error -> Idle
+ *                                                    \-> Cancelled -> Idle
  * ```
  *
  * Key transitions:
@@ -17,35 +18,40 @@
  * - IPC throws -> Error state with message
  * - FFmpeg non-zero exit -> Error via progress listener
  * - progress=100% -> auto-reset to Idle after 3s
+ * - cancelExport() -> backend kills FFmpeg, deletes the partial file and
+ *   emits state "cancelled" -> Idle
  */
 
 import { isTauri, tauriDialogs, tauriInvoke, tauriListen } from "../platform/tauriEnv";
 import { useVibeStore } from "../store/vibeStore";
 import { AspectRatio, VibeSettings } from "../types";
 import { renderTextOverlay } from "./renderTextOverlay";
+import { backgroundStyle } from "../utils/backgroundStyle";
+import { reactiveStrengths } from "../utils/reactiveBackground";
+import { toRustFit } from "../utils/backgroundFit";
+import { toRustSettings } from "../utils/engineSettings";
+import { toRustKenBurns } from "../utils/kenBurns";
 
 export interface ExportController {
   isSupported(): boolean;
   attachProgressListener(): Promise<() => void>;
   startExport(): Promise<void>;
+  cancelExport(): Promise<void>;
 }
 
 interface ExportProgressPayload {
   progress: number; // 0.0–1.0
   status: string;
+  state: "running" | "done" | "cancelled";
 }
 
 /** Map TS settings to Rust VibeSettings shape */
-function mapSettingsToRust(settings: VibeSettings) {
-  return {
-    visualizer_mode: settings.visualizerMode,
-    visualizer_color: settings.visualizerColor,
-    visualizer_intensity: settings.visualizerIntensity,
-  };
+export function mapSettingsToRust(settings: VibeSettings) {
+  return toRustSettings(settings);
 }
 
 /** Compute resolution from aspect ratio */
-function getResolution(aspectRatio: AspectRatio): { width: number; height: number } {
+export function getResolution(aspectRatio: AspectRatio): { width: number; height: number } {
   switch (aspectRatio) {
     case AspectRatio.OneOne:
       return { width: 1080, height: 1080 };
@@ -76,6 +82,11 @@ export function createExportController(): ExportController {
         // Ignore stale events if not exporting
         if (!isExporting) return;
 
+        if (event.payload.state === "cancelled") {
+          setExportState(false, 0, "Export cancelled");
+          return;
+        }
+
         const pct = Math.min(100, Math.max(0, Math.round(event.payload.progress * 100)));
         setExportState(true, pct, event.payload.status);
 
@@ -194,6 +205,11 @@ export function createExportController(): ExportController {
             width,
             height,
             show_progress: settings.showProgress,
+            background_fit: toRustFit(settings),
+            ken_burns: settings.kenBurns ? toRustKenBurns(settings) : null,
+            background_style: settings.blurBackground ? backgroundStyle(settings) : {},
+            reactive_background: settings.reactiveBackground ? reactiveStrengths(settings) : {},
+            background_playback: settings.backgroundPlayback,
             track_overlays: trackOverlays,
             track_boundaries: trackBoundaries,
           },
@@ -205,6 +221,16 @@ export function createExportController(): ExportController {
         setExportState(false, 0, `Error: ${message}`);
       }
     },
+
+    async cancelExport(): Promise<void> {
+      const { isExporting, exportProgress, setExportState } = useVibeStore.getState();
+      if (!isTauri() || !isExporting) return;
+
+      // Final state arrives as a "cancelled" progress event
+      setExportState(true, exportProgress, "Cancelling...");
+      const invoke = await tauriInvoke();
+      await invoke("cancel_export");
+    },
   };
 }
 
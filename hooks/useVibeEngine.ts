@@ -33,6 +33,7 @@ export const useVibeEngine = () => {
     currentTime,
     duration,
     analyser: AudioSystem.getInstance().getAnalyser(),
+    channelAnalysers: AudioSystem.getInstance().getChannelAnalysers(),
     addTracks: async (files: File[]) => {
       // Convert File[] to Track[] logic is needed here or in store
       const { generateId, getAudioDuration } = await import("../utils");
@@ -0,0 +1,224 @@
+/**
+ * ProjectController - Deep module owning `.vibe` project save/open.
+ *
+ * Hides: dialogs, store <-> project mapping (camelCase -> snake_case), IPC.
+ * Exposes: isSupported(), saveProject(), openProject()
+ *
+ * The file format (versioning, relative paths, migrations) lives in Rust
+ * (`crates/vibe-export/src/project.rs`); this side only maps store state.
+ * Status messages go through the export status line.
+ */
+
+import {
+  isTauri,
+  normalizeFilePath,
+  tauriConvertFileSrc,
+  tauriDialogs,
+  tauriInvoke,
+} from "../platform/tauriEnv";
+import { useVibeStore } from "../store/vibeStore";
+import {
+  AspectRatio,
+  BackgroundPlayback,
+  FontFamily,
+  FontSize,
+  Track,
+  VibeSettings,
+} from "../types";
+import { generateId } from "../utils";
+import { getResolution, mapSettingsToRust } from "../export/ExportController";
+import { RustBackgroundFit, fromRustFit, toRustFit } from "../utils/backgroundFit";
+import { RustSettings, fromRustSettings } from "../utils/engineSettings";
+import { RustKenBurns, fromRustKenBurns, toRustKenBurns } from "../utils/kenBurns";
+import { BackgroundStyle, backgroundStyle, fromRustStyle } from "../utils/backgroundStyle";
+import {
+  ReactiveBackground,
+  fromRustReactive,
+  reactiveStrengths,
+} from "../utils/reactiveBackground";
+
+const PROJECT_FILTER = { name: "Vibe Project", extensions: ["vibe"] };
+
+/** Rust `Project` shape (paths absolute on the IPC boundary) */
+export interface ProjectFile {
+  tracks: Array<{ path: string; name: string; artist: string; duration: number }>;
+  background: {
+    image: string | null;
+    fit?: RustBackgroundFit;
+    ken_burns: boolean;
+    ken_burns_motion?: RustKenBurns;
+    blur: boolean;
+    style?: BackgroundStyle;
+    reactive: boolean;
+    reactive_strengths?: ReactiveBackground;
+    playback?: BackgroundPlayback;
+  };
+  settings: RustSettings;
+  overlay: { show_title: boolean; font_family: string; font_size: number };
+  output: { width: number; height: number; fps: number; show_progress: boolean };
+}
+
+interface LoadedProject {
+  project: ProjectFile;
+  missing: string[];
+}
+
+export interface ProjectController {
+  isSupported(): boolean;
+  saveProject(): Promise<void>;
+  openProject(): Promise<void>;
+}
+
+/** Store state -> project. Throws if a track has no file on disk. */
+export function toProject(
+  settings: VibeSettings,
+  playlist: Track[],
+  backgroundImagePath: string | null
+): ProjectFile {
+  const unsaved = playlist.filter((t) => !t.sourcePath).length;
+  if (unsaved > 0) {
+    throw new Error(`${unsaved} track${unsaved > 1 ? "s" : ""} missing source file${unsaved > 1 ? "s" : ""}`);
+  }
+
+  const { width, height } = getResolution(settings.aspectRatio);
+  return {
+    tracks: playlist.map((t) => ({
+      path: t.sourcePath as string,
+      name: t.name,
+      artist: t.artist,
+      duration: t.duration,
+    })),
+    background: {
+      image: backgroundImagePath,
+      fit: toRustFit(settings),
+      ken_burns: settings.kenBurns,
+      ken_burns_motion: toRustKenBurns(settings),
+      blur: settings.blurBackground,
+      style: backgroundStyle(settings),
+      reactive: settings.reactiveBackground,
+      reactive_strengths: reactiveStrengths(settings),
+      playback: settings.backgroundPlayback,
+    },
+    settings: mapSettingsToRust(settings),
+    overlay: {
+      show_title: settings.showTitle,
+      font_family: settings.fontFamily,
+      font_size: settings.fontSize,
+    },
+    output: { width, height, fps: 30, show_progress: settings.showProgress },
+  };
+}
+
+/** Project -> store settings and tracks (new track ids). */
+export function fromProject(project: ProjectFile): { settings: VibeSettings; playlist: Track[] } {
+  const { width, height } = project.output;
+  const aspectRatio =
+    width > height
+      ? AspectRatio.SixteenNine
+      : width === height
+        ? AspectRatio.OneOne
+        : AspectRatio.NineSixteen;
+
+  return {
+    settings: {
+      ...fromRustSettings(project.settings),
+      aspectRatio,
+      fontFamily: project.overlay.font_family as FontFamily,
+      fontSize: project.overlay.font_size as FontSize,
+      showTitle: project.overlay.show_title,
+      showProgress: project.output.show_progress,
+      kenBurns: project.background.ken_burns,
+      ...fromRustKenBurns(project.background.ken_burns_motion),
+      blurBackground: project.background.blur,
+      ...fromRustStyle(project.background.style),
+      reactiveBackground: project.background.reactive ?? false,
+      ...fromRustReactive(project.background.reactive_strengths),
+      ...fromRustFit(project.background.fit),
+      backgroundPlayback: project.background.playback ?? BackgroundPlayback.Loop,
+    },
+    playlist: project.tracks.map((t) => ({
+      id: generateId(),
+      sourcePath: t.path,
+      name: t.name,
+      artist: t.artist,
+      duration: t.duration,
+    })),
+  };
+}
+
+/** Create a ProjectController instance */
+export function createProjectController(): ProjectController {
+  return {
+    isSupported(): boolean {
+      return isTauri();
+    },
+
+    async saveProject(): Promise<void> {
+      const { settings, playlist, backgroundImagePath, isExporting, setExportState } =
+        useVibeStore.getState();
+      if (!isTauri() || isExporting) return;
+
+      try {
+        const project = toProject(settings, playlist, backgroundImagePath);
+        const dialogs = await tauriDialogs();
+        const pathRaw = await dialogs.save({
+          filters: [PROJECT_FILTER],
+          defaultPath: playlist[0]?.name ? `${playlist[0].name}.vibe` : undefined,
+        });
+        if (!pathRaw) return; // Cancelled
+
+        const path = /\.vibe$/i.test(pathRaw) ? pathRaw : `${pathRaw}.vibe`;
+        const invoke = await tauriInvoke();
+        await invoke("save_project", { path, project });
+        setExportState(false, 0, "Project saved");
+      } catch (error) {
+        const message = error instanceof Error ? error.message : String(error);
+        setExportState(false, 0, `Error: ${message}`);
+      }
+    },
+
+    async openProject(): Promise<void> {
+      const { backgroundImage, isExporting, setExportState, restoreSession } =
+        useVibeStore.getState();
+      if (!isTauri() || isExporting) return;
+
+      try {
+        const dialogs = await tauriDialogs();
+        const pathRaw = await dialogs.open({ filters: [PROJECT_FILTER], multiple: false });
+        if (!pathRaw || Array.isArray(pathRaw)) return; // Cancelled
+
+        const invoke = await tauriInvoke();
+        const { project, missing } = await invoke<LoadedProject>("load_project", {
+          path: normalizeFilePath(pathRaw),
+        });
+        const { settings, playlist } = fromProject(project);
+        const convertFileSrc = await tauriConvertFileSrc();
+
+        if (backgroundImage?.startsWith("blob:")) {
+          URL.revokeObjectURL(backgroundImage);
+        }
+        const imagePath = project.background.image;
+        restoreSession({
+          settings,
+          playlist,
+          backgroundImage: imagePath ? convertFileSrc(imagePath) : null,
+          backgroundImagePath: imagePath,
+        });
+
+        setExportState(
+          false,
+          0,
+          missing.length > 0
+            ? `Project opened: ${missing.length} file${missing.length > 1 ? "s" : ""} not found`
+            : "Project opened"
+        );
+      } catch (error) {
+        const message = error instanceof Error ? error.message : String(error);
+        setExportState(false, 0, `Error: ${message}`);
+      }
+    },
+  };
+}
+
+/** Singleton controller instance */
+export const projectController = createProjectController();
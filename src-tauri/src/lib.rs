@@ -1,8 +1,9 @@
 mod export_video;
-mod export_frame;
 mod path_guard;
+mod project;
 
-use export_video::export_video;
+use export_video::{cancel_export, export_video, ActiveExport};
+use project::{load_project, save_project};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -11,7 +12,13 @@ pub fn run() {
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_shell::init()) // Init shell plugin
     .plugin(tauri_plugin_log::Builder::default().level(log::LevelFilter::Info).build())
-    .invoke_handler(tauri::generate_handler![export_video])
+    .manage(ActiveExport::default())
+    .invoke_handler(tauri::generate_handler![
+      export_video,
+      cancel_export,
+      save_project,
+      load_project
+    ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
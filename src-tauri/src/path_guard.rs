@@ -129,6 +129,44 @@ fn validate_output_and_image(
     Ok((output, image))
 }
 
+/// Validate a `.vibe` project path. Opening requires an existing file;
+/// saving requires an existing parent directory.
+///
+/// # Errors
+/// Returns human-readable error string on validation failure.
+pub fn guard_project_path(path: &str, must_exist: bool) -> Result<PathBuf, String> {
+    let project = PathBuf::from(path);
+    if !project.is_absolute() {
+        return Err("project path must be absolute".into());
+    }
+
+    let ext = project
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    if ext.as_deref() != Some("vibe") {
+        return Err("project path must end with .vibe".into());
+    }
+
+    if must_exist {
+        let project = project
+            .canonicalize()
+            .map_err(|e| format!("project path canonicalize failed: {}", e))?;
+        if !project.is_file() {
+            return Err("project path must be a file".into());
+        }
+        return Ok(project);
+    }
+
+    let parent = project
+        .parent()
+        .ok_or("project path missing parent directory")?;
+    if !parent.is_dir() {
+        return Err("project directory does not exist".into());
+    }
+    Ok(project)
+}
+
 /// Validate and canonicalize export paths (single audio).
 /// Used by tests and kept for backwards compatibility.
 ///
@@ -185,7 +223,7 @@ mod tests {
 
         let result = guard_export_paths("relative/path.mp3", output.to_str().unwrap(), "");
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("audio_path must be absolute"));
+        assert!(result.unwrap_err().contains("audio path must be absolute"));
     }
 
     #[test]
@@ -368,4 +406,33 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("image_path canonicalize failed"));
     }
+
+    #[test]
+    fn project_save_requires_vibe_extension_and_directory() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("session.vibe");
+
+        assert!(guard_project_path(project.to_str().unwrap(), false).is_ok());
+        assert!(guard_project_path("relative/session.vibe", false).is_err());
+
+        let wrong_ext = dir.path().join("session.json");
+        let result = guard_project_path(wrong_ext.to_str().unwrap(), false);
+        assert!(result.unwrap_err().contains("must end with .vibe"));
+
+        let missing_dir = dir.path().join("missing/session.vibe");
+        let result = guard_project_path(missing_dir.to_str().unwrap(), false);
+        assert!(result.unwrap_err().contains("directory does not exist"));
+    }
+
+    #[test]
+    fn project_open_requires_existing_file() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("session.vibe");
+
+        let result = guard_project_path(project.to_str().unwrap(), true);
+        assert!(result.unwrap_err().contains("canonicalize failed"));
+
+        File::create(&project).unwrap();
+        assert!(guard_project_path(project.to_str().unwrap(), true).is_ok());
+    }
 }
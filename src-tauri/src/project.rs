@@ -0,0 +1,32 @@
+//! Tauri commands for `.vibe` project files. The format itself (schema,
+//! relative paths, migrations) lives in `vibe-export`, shared with the CLI.
+
+use crate::path_guard::guard_project_path;
+use std::path::PathBuf;
+use vibe_export::Project;
+
+#[derive(serde::Serialize)]
+pub struct LoadedProject {
+    pub project: Project,
+    /// Referenced files that no longer exist, so the UI can warn.
+    pub missing: Vec<PathBuf>,
+}
+
+#[tauri::command]
+pub fn save_project(path: String, project: Project) -> Result<(), String> {
+    let path = guard_project_path(&path, false)?;
+    project.save(&path)?;
+    log::info!("Saved project: {} tracks", project.tracks.len());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn load_project(path: String) -> Result<LoadedProject, String> {
+    let path = guard_project_path(&path, true)?;
+    let project = Project::load(&path)?;
+    let missing = project.missing_files();
+    if !missing.is_empty() {
+        log::warn!("Project references {} missing files", missing.len());
+    }
+    Ok(LoadedProject { project, missing })
+}
@@ -1,7 +1,19 @@
 import { create } from "zustand";
 import { subscribeWithSelector } from "zustand/middleware";
 import { LazyStore } from "@tauri-apps/plugin-store";
-import { VibeSettings, VisualizerMode, AspectRatio, FontFamily, FontSize, Track } from "../types";
+import {
+  VibeSettings,
+  AspectRatio,
+  FontFamily,
+  FontSize,
+  Track,
+  BackgroundFit,
+  BackgroundPlayback,
+} from "../types";
+import { ENGINE_DEFAULTS } from "../utils/engineSettings";
+import { fromRustKenBurns } from "../utils/kenBurns";
+import { fromRustStyle } from "../utils/backgroundStyle";
+import { fromRustReactive } from "../utils/reactiveBackground";
 
 const settingsStore = new LazyStore("settings.json");
 
@@ -38,6 +50,14 @@ interface VibeState {
   setBackgroundImage: (url: string | null) => void;
   setBackgroundImagePath: (path: string | null) => void;
 
+  // Session restore (project open): replaces settings, playlist and background
+  restoreSession: (session: {
+    settings: VibeSettings;
+    playlist: Track[];
+    backgroundImage: string | null;
+    backgroundImagePath: string | null;
+  }) => void;
+
   // Export State
   isExporting: boolean;
   exportProgress: number;
@@ -48,16 +68,24 @@ interface VibeState {
 }
 
 const DEFAULT_SETTINGS: VibeSettings = {
-  visualizerMode: VisualizerMode.Bars,
+  ...ENGINE_DEFAULTS,
   aspectRatio: AspectRatio.SixteenNine,
   fontFamily: FontFamily.Geist,
   fontSize: FontSize.Medium,
   showTitle: true,
   showProgress: true,
   kenBurns: true,
+  ...fromRustKenBurns(undefined),
   blurBackground: false,
-  visualizerColor: "#ffb703", // Plasma
-  visualizerIntensity: 1.0,
+  ...fromRustStyle(undefined),
+  reactiveBackground: false,
+  ...fromRustReactive(undefined),
+  backgroundFit: BackgroundFit.Cover,
+  backgroundFocusX: 0.5,
+  backgroundFocusY: 0.5,
+  backgroundFill: "#030304",
+  backgroundFillBottom: null,
+  backgroundPlayback: BackgroundPlayback.Loop,
 };
 
 export const useVibeStore = create<VibeState>()(
@@ -147,6 +175,17 @@ export const useVibeStore = create<VibeState>()(
     setBackgroundImage: (url) => set({ backgroundImage: url }),
     setBackgroundImagePath: (path) => set({ backgroundImagePath: path }),
 
+    // Session
+    restoreSession: ({ settings, playlist, backgroundImage, backgroundImagePath }) =>
+      set({
+        settings: { ...DEFAULT_SETTINGS, ...settings },
+        playlist,
+        currentTrackId: playlist[0]?.id ?? null,
+        isPlaying: false,
+        backgroundImage,
+        backgroundImagePath,
+      }),
+
     // Export
     isExporting: false,
     exportProgress: 0,
@@ -34,6 +34,11 @@ const mockGain = {
   gain: { value: 1 },
 };
 
+const mockSplitter = {
+  connect: vi.fn(),
+  channelInterpretation: "discrete",
+};
+
 const mockMediaSource = {
   connect: vi.fn(),
 };
@@ -46,6 +51,7 @@ class MockAudioContext {
   createAnalyser = vi.fn(() => mockAnalyser);
   createGain = vi.fn(() => mockGain);
   createMediaElementSource = vi.fn(() => mockMediaSource);
+  createChannelSplitter = vi.fn(() => mockSplitter);
   destination = {};
 }
 
@@ -122,5 +128,6 @@ describe("AudioSystem", () => {
 
     // Now analyser should be available
     expect(instance.getAnalyser()).toBeTruthy();
+    expect(instance.getChannelAnalysers()).toHaveLength(2);
   });
 });
@@ -0,0 +1,117 @@
+import { describe, expect, it, vi } from "vitest";
+
+vi.mock("@tauri-apps/plugin-store", () => {
+  class MockLazyStore {
+    constructor(_path: string) {}
+    async get<T>(_key: string): Promise<T | null> {
+      return null;
+    }
+    async set(_key: string, _value: unknown) {}
+    async save() {}
+  }
+
+  return { LazyStore: MockLazyStore };
+});
+
+import { fromProject, toProject } from "../project/ProjectController";
+import {
+  AspectRatio,
+  BackgroundFit,
+  BackgroundPlayback,
+  BandScale,
+  BarAnchor,
+  BarLayout,
+  FontFamily,
+  FontSize,
+  VibeSettings,
+  VisualizerMode,
+  WaveLayout,
+} from "../types";
+
+const settings: VibeSettings = {
+  visualizerMode: VisualizerMode.Wave,
+  aspectRatio: AspectRatio.NineSixteen,
+  fontFamily: FontFamily.Mono,
+  fontSize: FontSize.Large,
+  showTitle: false,
+  showProgress: true,
+  kenBurns: true,
+  kenBurnsSpeed: 2,
+  kenBurnsZoomMin: 1.1,
+  kenBurnsZoomMax: 1.25,
+  kenBurnsSeed: 5,
+  blurBackground: false,
+  backgroundBlur: 20,
+  backgroundDim: 0.5,
+  backgroundDesaturate: 1,
+  reactiveBackground: true,
+  reactiveZoomPulse: 1,
+  reactiveBrightnessPump: 0,
+  reactiveChromatic: 0.25,
+  reactiveShake: 0.75,
+  backgroundFit: BackgroundFit.Contain,
+  backgroundFocusX: 0.5,
+  backgroundFocusY: 0.2,
+  backgroundFill: "#101820",
+  backgroundFillBottom: "#000000",
+  backgroundPlayback: BackgroundPlayback.PingPong,
+  visualizerColor: "#06b6d4",
+  visualizerIntensity: 0.6,
+  visualizerPalette: {
+    kind: "Amplitude",
+    stops: [
+      { offset: 0, color: "#06b6d4" },
+      { offset: 1, color: "#ec4899" },
+    ],
+  },
+  barCount: 32,
+  barGapRatio: 0.25,
+  barAnchor: BarAnchor.FullWidth,
+  barLayout: BarLayout.Stereo,
+  attackSecs: 0.01,
+  decaySecs: 0.3,
+  glowStrength: 0.8,
+  glowRadius: 16,
+  glowThreshold: 0.35,
+  spectrogramSeconds: 8,
+  particleSeed: 3735928559,
+  particleDensity: 1.5,
+  bandScale: BandScale.Mel,
+  bandMinHz: 40,
+  bandMaxHz: 12000,
+  autoGain: true,
+  autoGainMaxDb: 12,
+  autoGainReleaseSecs: 2,
+  waveLayout: WaveLayout.Stereo,
+};
+
+const track = { id: "a", sourcePath: "/music/a.mp3", name: "A", artist: "Band", duration: 90 };
+
+describe("ProjectController mapping", () => {
+  it("round-trips settings and tracks", () => {
+    const project = toProject(settings, [track], "/img/bg.png");
+    expect(project.output).toMatchObject({ width: 1080, height: 1920 });
+    expect(project.background).toEqual({
+      image: "/img/bg.png",
+      fit: { mode: "contain", focus: [0.5, 0.2], fill: { gradient: ["#101820", "#000000"] } },
+      ken_burns: true,
+      ken_burns_motion: { speed: 2, zoom_min: 1.1, zoom_max: 1.25, pan: 0.014, seed: 5 },
+      blur: false,
+      style: { blur: 20, dim: 0.5, desaturate: 1 },
+      reactive: true,
+      reactive_strengths: { zoom_pulse: 1, brightness_pump: 0, chromatic: 0.25, shake: 0.75 },
+      playback: "ping_pong",
+    });
+
+    const restored = fromProject(project);
+    expect(restored.settings).toEqual(settings);
+    expect(restored.playlist).toHaveLength(1);
+    expect(restored.playlist[0]).toMatchObject({ sourcePath: "/music/a.mp3", name: "A", artist: "Band" });
+    expect(restored.playlist[0].id).not.toBe("a");
+  });
+
+  it("refuses tracks without a source file", () => {
+    const { sourcePath: _, ...browserOnly } = track;
+    expect(() => toProject(settings, [browserOnly], null)).toThrow(/missing source file/);
+  });
+});
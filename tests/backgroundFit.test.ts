@@ -0,0 +1,45 @@
+import { describe, expect, it } from "vitest";
+import { BackgroundFit } from "../types";
+import { fitSize, focusOffset, fromRustFit, tileStarts } from "../utils/backgroundFit";
+
+describe("background fit", () => {
+  it("sizes square art for a 16:9 frame", () => {
+    expect(fitSize(BackgroundFit.Cover, 1920, 1080, 500, 500)).toEqual({ w: 1920, h: 1920 });
+    expect(fitSize(BackgroundFit.Contain, 1920, 1080, 500, 500)).toEqual({ w: 1080, h: 1080 });
+    expect(fitSize(BackgroundFit.Tile, 1920, 1080, 500, 500)).toEqual({ w: 540, h: 540 });
+    expect(fitSize(BackgroundFit.Stretch, 1920, 1080, 500, 500)).toEqual({ w: 1920, h: 1080 });
+  });
+
+  it("keeps a thin strip's tile within the frame", () => {
+    expect(fitSize(BackgroundFit.Tile, 1920, 1080, 10, 3840)).toEqual({ w: 5, h: 1920 });
+  });
+
+  it("centres one tile and covers the frame", () => {
+    const starts = tileStarts(1920, 540);
+    expect(starts).toContain((1920 - 540) / 2);
+    expect(starts[0]).toBeLessThanOrEqual(0);
+    expect(starts[starts.length - 1] + 540).toBeGreaterThanOrEqual(1920);
+  });
+
+  it("starts tiling from the focus point", () => {
+    expect(tileStarts(1920, 540, 0)).toContain(0);
+    expect(tileStarts(1920, 540, 1)).toContain(1920 - 540);
+  });
+
+  it("slides the cover overflow toward the focus point", () => {
+    // 840px of vertical overflow: focus at the top shows the top edge
+    expect(focusOffset(0, 1920, 1080, 1)).toBe(420);
+    expect(focusOffset(0.5, 1920, 1080, 1)).toBe(0);
+    expect(focusOffset(1, 1920, 1080, 1)).toBe(-420);
+  });
+
+  it("defaults missing project fields", () => {
+    expect(fromRustFit(undefined)).toEqual({
+      backgroundFit: BackgroundFit.Cover,
+      backgroundFocusX: 0.5,
+      backgroundFocusY: 0.5,
+      backgroundFill: "#030304",
+      backgroundFillBottom: null,
+    });
+  });
+});
@@ -0,0 +1,16 @@
+import { describe, expect, it } from "vitest";
+import { ENGINE_DEFAULTS, fromRustSettings, toRustSettings } from "../utils/engineSettings";
+import { VibeSettings } from "../types";
+
+describe("engine settings", () => {
+  it("defaults missing project fields", () => {
+    expect(fromRustSettings(undefined)).toEqual(ENGINE_DEFAULTS);
+    expect(fromRustSettings({ bar_count: 48 })).toEqual({ ...ENGINE_DEFAULTS, barCount: 48 });
+  });
+
+  it("forwards every engine field", () => {
+    const rust = toRustSettings(ENGINE_DEFAULTS as VibeSettings);
+    expect(Object.values(rust)).not.toContain(undefined);
+    expect(fromRustSettings(rust)).toEqual(ENGINE_DEFAULTS);
+  });
+});
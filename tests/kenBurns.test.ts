@@ -0,0 +1,36 @@
+import { describe, expect, it } from "vitest";
+import { KEN_BURNS_DEFAULTS, fromRustKenBurns, kenBurnsTransform } from "../utils/kenBurns";
+
+describe("kenBurnsTransform", () => {
+  it("stays within the zoom range and keeps the image covering the frame", () => {
+    for (let time = 0; time < 600; time += 3.7) {
+      const { scale, tx, ty } = kenBurnsTransform(time, 1080, 1920, 1080, 1920);
+      expect(scale).toBeGreaterThanOrEqual(1.03);
+      expect(scale).toBeLessThanOrEqual(1.07);
+      expect(Math.abs(tx)).toBeLessThanOrEqual((1080 * scale - 1080) / 2);
+      expect(Math.abs(ty)).toBeLessThanOrEqual((1920 * scale - 1920) / 2);
+    }
+  });
+
+  it("flips directions by seed bit", () => {
+    const base = kenBurnsTransform(40, 1920, 1080, 1920, 1080);
+    const flipped = kenBurnsTransform(40, 1920, 1080, 1920, 1080, {
+      ...KEN_BURNS_DEFAULTS,
+      seed: 0b011,
+    });
+    expect(flipped.tx).toBeCloseTo(-base.tx);
+    expect(flipped.ty).toBeCloseTo(-base.ty);
+    expect(flipped.scale).toBeCloseTo(base.scale);
+  });
+});
+
+describe("fromRustKenBurns", () => {
+  it("defaults missing project fields", () => {
+    expect(fromRustKenBurns({ speed: 2 })).toEqual({
+      kenBurnsSpeed: 2,
+      kenBurnsZoomMin: 1.03,
+      kenBurnsZoomMax: 1.07,
+      kenBurnsSeed: 0,
+    });
+  });
+});
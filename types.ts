@@ -11,6 +11,9 @@ export enum VisualizerMode {
   Bars = "Bars",
   Orbital = "Orbital",
   Wave = "Wave",
+  Spectrogram = "Spectrogram",
+  Particles = "Particles",
+  Goniometer = "Goniometer",
 }
 
 export enum FontFamily {
@@ -36,6 +39,57 @@ export enum AspectRatio {
   OneOne = "1/1",
 }
 
+export enum BackgroundFit {
+  Cover = "cover",
+  Contain = "contain",
+  BlurredFill = "blurred_fill",
+  Tile = "tile",
+  Stretch = "stretch",
+}
+
+/** How a GIF or video background repeats (Rust `Playback`) */
+export enum BackgroundPlayback {
+  Loop = "loop",
+  PingPong = "ping_pong", // forward then backward
+}
+
+export enum BarAnchor {
+  BottomLeft = "BottomLeft",
+  BottomCenter = "BottomCenter",
+  BottomRight = "BottomRight",
+  Top = "Top",
+  FullWidth = "FullWidth",
+}
+
+export enum BarLayout {
+  Standard = "Standard",
+  Mirrored = "Mirrored",
+  Centered = "Centered",
+  Stereo = "Stereo", // left channel on the left half, right on the right
+}
+
+export enum WaveLayout {
+  Mono = "Mono",
+  Stereo = "Stereo", // left above the center line, right below
+}
+
+export enum BandScale {
+  Linear = "Linear",
+  Log = "Log",
+  Mel = "Mel",
+  Bark = "Bark",
+}
+
+export interface ColorStop {
+  offset: number; // 0 to 1 along the gradient
+  color: string;
+}
+
+/** Rust `Palette`: Solid uses visualizerColor; the others span the visualizer */
+export type Palette =
+  | { kind: "Solid" }
+  | { kind: "Vertical" | "Horizontal" | "Radial" | "Amplitude"; stops: ColorStop[] };
+
 export interface VibeSettings {
   visualizerMode: VisualizerMode;
   aspectRatio: AspectRatio;
@@ -44,9 +98,47 @@ export interface VibeSettings {
   showTitle: boolean;
   showProgress: boolean;
   kenBurns: boolean;
+  kenBurnsSpeed: number; // 1 = one zoom cycle every ~2 minutes
+  kenBurnsZoomMin: number;
+  kenBurnsZoomMax: number;
+  kenBurnsSeed: number; // bits pick the pan/zoom directions
   blurBackground: boolean;
+  backgroundBlur: number; // px at 1080p
+  backgroundDim: number; // 0 to 1
+  backgroundDesaturate: number; // 0 to 1
+  reactiveBackground: boolean;
+  reactiveZoomPulse: number; // 0 to 1 strengths, 0 = off
+  reactiveBrightnessPump: number;
+  reactiveChromatic: number;
+  reactiveShake: number;
+  backgroundFit: BackgroundFit;
+  backgroundFocusX: number; // 0 to 1, cover crop focus / tile anchor
+  backgroundFocusY: number;
+  backgroundFill: string; // letterbox / no-image colour
+  backgroundFillBottom: string | null; // gradient end; null = solid
+  backgroundPlayback: BackgroundPlayback;
   visualizerColor: string;
   visualizerIntensity: number; // 0 to 1
+  visualizerPalette: Palette;
+  barCount: number; // bands (bars, or Orbital spoke pairs)
+  barGapRatio: number; // gap as a fraction of bar width
+  barAnchor: BarAnchor;
+  barLayout: BarLayout;
+  attackSecs: number; // rise time constant
+  decaySecs: number; // fall time constant
+  glowStrength: number; // 0 disables the bloom pass
+  glowRadius: number; // px at 1080p
+  glowThreshold: number; // 0 to 1 luminance
+  spectrogramSeconds: number; // history shown by Spectrogram
+  particleSeed: number; // u32; same seed + same audio = same frames
+  particleDensity: number; // emission rate multiplier
+  bandScale: BandScale; // frequency axis bands are spaced on
+  bandMinHz: number;
+  bandMaxHz: number; // capped at Nyquist
+  autoGain: boolean; // follow the rolling spectral peak
+  autoGainMaxDb: number; // largest boost or cut
+  autoGainReleaseSecs: number; // recovery after a loud passage
+  waveLayout: WaveLayout;
 }
 
 export const PRESET_COLORS = [
@@ -0,0 +1,158 @@
+/**
+ * Background fit modes, crop focus and fill. The preview draws them on the
+ * canvas; the export (`BackgroundFit` in `crates/vibe-export/src/frame.rs`)
+ * lays the image out the same way.
+ */
+
+import { BackgroundFit, VibeSettings } from "../types";
+
+/** Backdrop of "Blur Fill": blur (px at 1080p) and dim, as in the export */
+export const BLURRED_FILL = { blur: 40, dim: 0.45 };
+
+/** Rust `BackgroundFit` shape */
+export interface RustBackgroundFit {
+  mode: BackgroundFit;
+  focus: [number, number];
+  fill: { solid: string } | { gradient: [string, string] };
+}
+
+export function toRustFit(settings: VibeSettings): RustBackgroundFit {
+  return {
+    mode: settings.backgroundFit,
+    focus: [settings.backgroundFocusX, settings.backgroundFocusY],
+    fill: settings.backgroundFillBottom
+      ? { gradient: [settings.backgroundFill, settings.backgroundFillBottom] }
+      : { solid: settings.backgroundFill },
+  };
+}
+
+/** Settings fields of a Rust `BackgroundFit`; omitted = the defaults */
+export function fromRustFit(fit: Partial<RustBackgroundFit> | undefined) {
+  const fill = fit?.fill;
+  return {
+    backgroundFit: fit?.mode ?? BackgroundFit.Cover,
+    backgroundFocusX: fit?.focus?.[0] ?? 0.5,
+    backgroundFocusY: fit?.focus?.[1] ?? 0.5,
+    backgroundFill: fill ? ("solid" in fill ? fill.solid : fill.gradient[0]) : "#030304",
+    backgroundFillBottom: fill && "gradient" in fill ? fill.gradient[1] : null,
+  };
+}
+
+/**
+ * Image size in a `width` x `height` frame: cover/contain scale, the frame
+ * for stretch, one tile (half the shorter side, long side within the frame) for tile.
+ */
+export function fitSize(
+  mode: BackgroundFit,
+  width: number,
+  height: number,
+  imgWidth: number,
+  imgHeight: number
+): { w: number; h: number } {
+  let scale: number;
+  switch (mode) {
+    case BackgroundFit.Stretch:
+      return { w: width, h: height };
+    case BackgroundFit.Contain:
+    case BackgroundFit.BlurredFill:
+      scale = Math.min(width / imgWidth, height / imgHeight);
+      break;
+    case BackgroundFit.Tile:
+      scale = Math.min(
+        Math.min(width, height) / 2 / Math.min(imgWidth, imgHeight),
+        Math.max(width, height) / Math.max(imgWidth, imgHeight)
+      );
+      break;
+    case BackgroundFit.Cover:
+    default:
+      scale = Math.max(width / imgWidth, height / imgHeight);
+  }
+  return { w: imgWidth * scale, h: imgHeight * scale };
+}
+
+/** Tile start positions along `length`, one tile at `focus` (0..1, centred by default) */
+export function tileStarts(length: number, tile: number, focus = 0.5): number[] {
+  const anchor = (length - tile) * Math.min(1, Math.max(0, focus));
+  const starts: number[] = [];
+  for (let p = (((anchor % tile) + tile) % tile) - tile; p < length; p += tile) {
+    starts.push(p);
+  }
+  return starts;
+}
+
+/** Offset of a cover-fit image of `size` (at `scale`) keeping `focus` (0..1) in view */
+export function focusOffset(focus: number, size: number, frame: number, scale: number): number {
+  return (0.5 - focus) * Math.max(0, size * scale - frame);
+}
+
+type Ctx = CanvasRenderingContext2D | OffscreenCanvasRenderingContext2D;
+
+/** Fill the rect with the solid colour or top-to-bottom gradient */
+export function paintFill(
+  ctx: Ctx,
+  settings: VibeSettings,
+  x: number,
+  y: number,
+  w: number,
+  h: number
+) {
+  if (settings.backgroundFillBottom) {
+    const grad = ctx.createLinearGradient(0, y, 0, y + h);
+    grad.addColorStop(0, settings.backgroundFill);
+    grad.addColorStop(1, settings.backgroundFillBottom);
+    ctx.fillStyle = grad;
+  } else {
+    ctx.fillStyle = settings.backgroundFill;
+  }
+  ctx.fillRect(x, y, w, h);
+}
+
+/**
+ * Draw `image` by the fit mode into a `w` x `h` rect centred on the origin:
+ * the cover size for cover, the frame (shape) for the other modes.
+ * `shortSide` scales the blurred fill to the frame.
+ */
+export function drawFitted(
+  ctx: Ctx,
+  image: CanvasImageSource,
+  settings: VibeSettings,
+  w: number,
+  h: number,
+  imgWidth: number,
+  imgHeight: number,
+  shortSide: number
+) {
+  const mode = settings.backgroundFit;
+  if (mode === BackgroundFit.Cover) {
+    ctx.drawImage(image, -w / 2, -h / 2, w, h);
+    return;
+  }
+
+  const size = fitSize(mode, w, h, imgWidth, imgHeight);
+  ctx.save();
+  // Tiles and the blurred backdrop overhang the frame-shaped rect
+  ctx.beginPath();
+  ctx.rect(-w / 2, -h / 2, w, h);
+  ctx.clip();
+  if (mode === BackgroundFit.BlurredFill) {
+    const cover = fitSize(BackgroundFit.Cover, w, h, imgWidth, imgHeight);
+    const base = ctx.filter && ctx.filter !== "none" ? `${ctx.filter} ` : "";
+    const filter = ctx.filter;
+    ctx.filter = `${base}blur(${(BLURRED_FILL.blur * shortSide) / 1080}px) brightness(${1 - BLURRED_FILL.dim})`;
+    ctx.drawImage(image, -cover.w / 2, -cover.h / 2, cover.w, cover.h);
+    ctx.filter = filter;
+  } else {
+    paintFill(ctx, settings, -w / 2, -h / 2, w, h);
+  }
+
+  if (mode === BackgroundFit.Tile) {
+    for (const y of tileStarts(h, size.h, settings.backgroundFocusY)) {
+      for (const x of tileStarts(w, size.w, settings.backgroundFocusX)) {
+        ctx.drawImage(image, x - w / 2, y - h / 2, size.w, size.h);
+      }
+    }
+  } else {
+    ctx.drawImage(image, -size.w / 2, -size.h / 2, size.w, size.h);
+  }
+  ctx.restore();
+}
@@ -0,0 +1,43 @@
+/**
+ * Background image look (blur, dim, desaturate). The preview applies it as a
+ * canvas filter; the export (`BackgroundStyle` in
+ * `crates/vibe-export/src/frame.rs`) applies the same numbers to the image.
+ */
+
+import { VibeSettings } from "../types";
+
+export interface BackgroundStyle {
+  /** Gaussian blur (standard deviation, px) at 1080p */
+  blur: number;
+  /** 0 = unchanged, 1 = black */
+  dim: number;
+  /** 0 = unchanged, 1 = greyscale */
+  desaturate: number;
+}
+
+/** Default look of the "Blur BG" toggle */
+export const BLURRED_BACKGROUND: BackgroundStyle = { blur: 12, dim: 0.35, desaturate: 0.2 };
+
+/** Style picked in settings (used when "Blur BG" is on) */
+export function backgroundStyle(settings: VibeSettings): BackgroundStyle {
+  return {
+    blur: settings.backgroundBlur,
+    dim: settings.backgroundDim,
+    desaturate: settings.backgroundDesaturate,
+  };
+}
+
+/** Settings fields of a Rust `BackgroundStyle`; omitted = the toggle's default look */
+export function fromRustStyle(style: Partial<BackgroundStyle> | undefined) {
+  return {
+    backgroundBlur: style?.blur ?? BLURRED_BACKGROUND.blur,
+    backgroundDim: style?.dim ?? BLURRED_BACKGROUND.dim,
+    backgroundDesaturate: style?.desaturate ?? BLURRED_BACKGROUND.desaturate,
+  };
+}
+
+/** Canvas filter for a `width` x `height` frame (blur scaled to its shorter side) */
+export function backgroundFilter(style: BackgroundStyle, width: number, height: number): string {
+  const blurPx = (style.blur * Math.min(width, height)) / 1080;
+  return `blur(${blurPx}px) saturate(${1 - style.desaturate}) brightness(${1 - style.dim})`;
+}
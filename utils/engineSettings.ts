@@ -0,0 +1,130 @@
+/**
+ * Engine settings: the visualizer fields of `VibeSettings` in the Rust
+ * `VibeSettings` shape (`crates/vibe-engine/src/lib.rs`). The preview (WASM),
+ * the export and `.vibe` projects all go through this one mapping so a
+ * setting can't reach one and not the others.
+ */
+
+import {
+  BandScale,
+  BarAnchor,
+  BarLayout,
+  Palette,
+  VibeSettings,
+  VisualizerMode,
+  WaveLayout,
+} from "../types";
+
+/** Engine defaults, as the Rust serde defaults */
+export const ENGINE_DEFAULTS = {
+  visualizerMode: VisualizerMode.Bars,
+  visualizerColor: "#ffb703", // Plasma
+  visualizerIntensity: 1.0,
+  visualizerPalette: { kind: "Solid" } as Palette,
+  barCount: 12,
+  barGapRatio: 0.5,
+  barAnchor: BarAnchor.BottomRight,
+  barLayout: BarLayout.Standard,
+  attackSecs: 0.018,
+  decaySecs: 0.13,
+  glowStrength: 0,
+  glowRadius: 24,
+  glowThreshold: 0.2,
+  spectrogramSeconds: 4,
+  particleSeed: 0,
+  particleDensity: 1,
+  bandScale: BandScale.Log,
+  bandMinHz: 30,
+  bandMaxHz: 16000,
+  autoGain: false,
+  autoGainMaxDb: 24,
+  autoGainReleaseSecs: 4,
+  waveLayout: WaveLayout.Mono,
+};
+
+export type EngineSettings = typeof ENGINE_DEFAULTS;
+
+/** Rust `VibeSettings` shape */
+export interface RustSettings {
+  visualizer_mode: VisualizerMode;
+  visualizer_color: string;
+  visualizer_intensity: number;
+  visualizer_palette: Palette;
+  bar_count: number;
+  bar_gap_ratio: number;
+  bar_anchor: BarAnchor;
+  bar_layout: BarLayout;
+  attack_secs: number;
+  decay_secs: number;
+  glow_strength: number;
+  glow_radius: number;
+  glow_threshold: number;
+  spectrogram_seconds: number;
+  particle_seed: number;
+  particle_density: number;
+  band_scale: BandScale;
+  band_min_hz: number;
+  band_max_hz: number;
+  auto_gain: boolean;
+  auto_gain_max_db: number;
+  auto_gain_release_secs: number;
+  wave_layout: WaveLayout;
+}
+
+export function toRustSettings(settings: VibeSettings): RustSettings {
+  return {
+    visualizer_mode: settings.visualizerMode,
+    visualizer_color: settings.visualizerColor,
+    visualizer_intensity: settings.visualizerIntensity,
+    visualizer_palette: settings.visualizerPalette,
+    bar_count: settings.barCount,
+    bar_gap_ratio: settings.barGapRatio,
+    bar_anchor: settings.barAnchor,
+    bar_layout: settings.barLayout,
+    attack_secs: settings.attackSecs,
+    decay_secs: settings.decaySecs,
+    glow_strength: settings.glowStrength,
+    glow_radius: settings.glowRadius,
+    glow_threshold: settings.glowThreshold,
+    spectrogram_seconds: settings.spectrogramSeconds,
+    particle_seed: settings.particleSeed,
+    particle_density: settings.particleDensity,
+    band_scale: settings.bandScale,
+    band_min_hz: settings.bandMinHz,
+    band_max_hz: settings.bandMaxHz,
+    auto_gain: settings.autoGain,
+    auto_gain_max_db: settings.autoGainMaxDb,
+    auto_gain_release_secs: settings.autoGainReleaseSecs,
+    wave_layout: settings.waveLayout,
+  };
+}
+
+/** Settings fields of a Rust `VibeSettings`; omitted = the defaults */
+export function fromRustSettings(rust: Partial<RustSettings> | undefined): EngineSettings {
+  const d = ENGINE_DEFAULTS;
+  return {
+    visualizerMode: rust?.visualizer_mode ?? d.visualizerMode,
+    visualizerColor: rust?.visualizer_color ?? d.visualizerColor,
+    visualizerIntensity: rust?.visualizer_intensity ?? d.visualizerIntensity,
+    visualizerPalette: rust?.visualizer_palette ?? d.visualizerPalette,
+    barCount: rust?.bar_count ?? d.barCount,
+    barGapRatio: rust?.bar_gap_ratio ?? d.barGapRatio,
+    barAnchor: rust?.bar_anchor ?? d.barAnchor,
+    barLayout: rust?.bar_layout ?? d.barLayout,
+    attackSecs: rust?.attack_secs ?? d.attackSecs,
+    decaySecs: rust?.decay_secs ?? d.decaySecs,
+    glowStrength: rust?.glow_strength ?? d.glowStrength,
+    glowRadius: rust?.glow_radius ?? d.glowRadius,
+    glowThreshold: rust?.glow_threshold ?? d.glowThreshold,
+    spectrogramSeconds: rust?.spectrogram_seconds ?? d.spectrogramSeconds,
+    particleSeed: rust?.particle_seed ?? d.particleSeed,
+    particleDensity: rust?.particle_density ?? d.particleDensity,
+    bandScale: rust?.band_scale ?? d.bandScale,
+    bandMinHz: rust?.band_min_hz ?? d.bandMinHz,
+    bandMaxHz: rust?.band_max_hz ?? d.bandMaxHz,
+    autoGain: rust?.auto_gain ?? d.autoGain,
+    autoGainMaxDb: rust?.auto_gain_max_db ?? d.autoGainMaxDb,
+    autoGainReleaseSecs: rust?.auto_gain_release_secs ?? d.autoGainReleaseSecs,
+    waveLayout: rust?.wave_layout ?? d.waveLayout,
+  };
+}
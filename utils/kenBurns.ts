@@ -0,0 +1,92 @@
+/**
+ * Ken Burns background motion, shared by the preview and (mirrored in Rust,
+ * `crates/vibe-export/src/frame.rs`) the export, so both move the same way.
+ */
+
+import { VibeSettings } from "../types";
+
+export interface KenBurnsOptions {
+  /** 1.0 = one zoom cycle every ~2 minutes */
+  speed: number;
+  zoomMin: number;
+  zoomMax: number;
+  /** Pan distance as a fraction of the frame's shorter side */
+  pan: number;
+  /** Bit 0 flips horizontal pan, bit 1 vertical, bit 2 starts by zooming out */
+  seed: number;
+}
+
+export const KEN_BURNS_DEFAULTS: KenBurnsOptions = {
+  speed: 1.0,
+  zoomMin: 1.03,
+  zoomMax: 1.07,
+  pan: 0.014,
+  seed: 0,
+};
+
+/** Rust `KenBurns` shape */
+export interface RustKenBurns {
+  speed: number;
+  zoom_min: number;
+  zoom_max: number;
+  pan: number;
+  seed: number;
+}
+
+/** Motion picked in settings; pan keeps the default */
+export function kenBurnsOptions(settings: VibeSettings): KenBurnsOptions {
+  return {
+    speed: settings.kenBurnsSpeed,
+    zoomMin: settings.kenBurnsZoomMin,
+    zoomMax: settings.kenBurnsZoomMax,
+    pan: KEN_BURNS_DEFAULTS.pan,
+    seed: settings.kenBurnsSeed,
+  };
+}
+
+export function toRustKenBurns(settings: VibeSettings): RustKenBurns {
+  const { speed, zoomMin, zoomMax, pan, seed } = kenBurnsOptions(settings);
+  return { speed, zoom_min: zoomMin, zoom_max: zoomMax, pan, seed };
+}
+
+/** Settings fields of a Rust `KenBurns`; omitted = the defaults */
+export function fromRustKenBurns(motion: Partial<RustKenBurns> | undefined) {
+  const d = KEN_BURNS_DEFAULTS;
+  return {
+    kenBurnsSpeed: motion?.speed ?? d.speed,
+    kenBurnsZoomMin: motion?.zoom_min ?? d.zoomMin,
+    kenBurnsZoomMax: motion?.zoom_max ?? d.zoomMax,
+    kenBurnsSeed: motion?.seed ?? d.seed,
+  };
+}
+
+/**
+ * Scale and pan offsets (pixels) at `timeSecs` for a `width` x `height`
+ * frame whose cover-fit image is `coverW` x `coverH`. Pan is clamped so the
+ * image edge never shows.
+ */
+export function kenBurnsTransform(
+  timeSecs: number,
+  width: number,
+  height: number,
+  coverW: number,
+  coverH: number,
+  options: KenBurnsOptions = KEN_BURNS_DEFAULTS
+): { scale: number; tx: number; ty: number } {
+  const t = ((timeSecs * options.speed) / 20) % (20 * Math.PI);
+  const flip = (bit: number) => ((options.seed >> bit) & 1 ? -1 : 1);
+
+  const lo = Math.max(1, options.zoomMin);
+  const hi = Math.max(lo, options.zoomMax);
+  const scale = (lo + hi) / 2 + (flip(2) * Math.sin(t) * (hi - lo)) / 2;
+
+  const pan = options.pan * Math.min(width, height);
+  const maxTx = Math.max(0, (coverW * scale - width) / 2);
+  const maxTy = Math.max(0, (coverH * scale - height) / 2);
+  const clamp = (v: number, max: number) => Math.min(max, Math.max(-max, v));
+  return {
+    scale,
+    tx: clamp(flip(0) * Math.cos(t * 0.5) * pan, maxTx),
+    ty: clamp(flip(1) * Math.sin(t * 0.3) * pan, maxTy),
+  };
+}
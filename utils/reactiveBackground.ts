@@ -0,0 +1,78 @@
+/**
+ * Music-driven background effects. Mirrors `ReactiveBackground` in
+ * `crates/vibe-export/src/frame.rs`; the preview applies the same zoom,
+ * pump, shake and chromatic aberration as the export.
+ */
+
+import { VibeSettings } from "../types";
+
+export interface ReactiveBackground {
+  /** Strengths 0..1 (0 = off) */
+  zoom_pulse: number;
+  brightness_pump: number;
+  chromatic: number;
+  shake: number;
+}
+
+/** Default mix of the "Reactive BG" toggle */
+export const REACTIVE_PULSE: ReactiveBackground = {
+  zoom_pulse: 0.5,
+  brightness_pump: 0.5,
+  chromatic: 0.5,
+  shake: 0.3,
+};
+
+/** Strengths picked in settings (used when "Reactive BG" is on) */
+export function reactiveStrengths(settings: VibeSettings): ReactiveBackground {
+  return {
+    zoom_pulse: settings.reactiveZoomPulse,
+    brightness_pump: settings.reactiveBrightnessPump,
+    chromatic: settings.reactiveChromatic,
+    shake: settings.reactiveShake,
+  };
+}
+
+/** Settings fields of a Rust `ReactiveBackground`; omitted = the toggle's default mix */
+export function fromRustReactive(style: Partial<ReactiveBackground> | undefined) {
+  return {
+    reactiveZoomPulse: style?.zoom_pulse ?? REACTIVE_PULSE.zoom_pulse,
+    reactiveBrightnessPump: style?.brightness_pump ?? REACTIVE_PULSE.brightness_pump,
+    reactiveChromatic: style?.chromatic ?? REACTIVE_PULSE.chromatic,
+    reactiveShake: style?.shake ?? REACTIVE_PULSE.shake,
+  };
+}
+
+/** Bass range (Hz) that drives zoom and pump */
+export const BASS_HZ: [number, number] = [20, 150];
+
+const ZOOM_PULSE_MAX = 0.06;
+const PUMP_MAX = 0.35;
+const ABERRATION_MAX = 0.008;
+const SHAKE_MAX = 0.012;
+
+/**
+ * Extra zoom, pan offsets (px), brightness and red/blue scale split for one
+ * frame. `bass` and `beat` are 0..1 from the engine; `shortSide` is the
+ * frame's shorter side.
+ */
+export function reactiveView(
+  style: ReactiveBackground,
+  bass: number,
+  beat: number,
+  timeSecs: number,
+  shortSide: number
+): { zoom: number; tx: number; ty: number; brightness: number; aberration: number } {
+  const clamp01 = (v: number) => Math.min(1, Math.max(0, v));
+  const [b, hit] = [clamp01(bass), clamp01(beat)];
+  const overscan = 1 + 2 * SHAKE_MAX * clamp01(style.shake);
+  const shake = SHAKE_MAX * clamp01(style.shake) * hit * shortSide;
+  const t = timeSecs;
+
+  return {
+    zoom: overscan * (1 + ZOOM_PULSE_MAX * clamp01(style.zoom_pulse) * b),
+    tx: (shake * (Math.sin(t * 53.0) + 0.5 * Math.cos(t * 31.7))) / 1.5,
+    ty: (shake * (Math.cos(t * 47.3) + 0.5 * Math.sin(t * 29.1))) / 1.5,
+    brightness: 1 - PUMP_MAX * clamp01(style.brightness_pump) * (1 - b),
+    aberration: ABERRATION_MAX * clamp01(style.chromatic) * hit,
+  };
+}